@@ -0,0 +1,33 @@
+// Starts `skibidyscript lsp` and hands .skibidi files to it, and `skibidyscript dap` to debug them
+
+const vscode = require("vscode");
+const { LanguageClient, TransportKind } = require("vscode-languageclient/node");
+
+let client;
+
+function activate(context) {
+    const command = vscode.workspace.getConfiguration("skibidiscript").get("serverPath", "skibidyscript");
+
+    const server = { command, args: ["lsp"], transport: TransportKind.stdio };
+
+    client = new LanguageClient(
+        "skibidiscript",
+        "Skibidiscript",
+        { run: server, debug: server },
+        { documentSelector: [{ scheme: "file", language: "skibidiscript" }] }
+    );
+
+    client.start();
+
+    context.subscriptions.push(
+        vscode.debug.registerDebugAdapterDescriptorFactory("skibidiscript", {
+            createDebugAdapterDescriptor: () => new vscode.DebugAdapterExecutable(command, ["dap"]),
+        })
+    );
+}
+
+function deactivate() {
+    return client ? client.stop() : undefined;
+}
+
+module.exports = { activate, deactivate };
@@ -0,0 +1,225 @@
+// cli.rs
+
+// Command line parsing. `skibidyscript <command> [flags] <script> [args...]`, where the command
+// can be left out to run the script like before there were commands.
+
+use crate::lint::LintKind;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Command {
+    Run,     // Check and run the script
+    Check,   // Parse, typecheck and lint without running anything
+    Tokens,  // What the lexer makes of the script
+    Ast,     // What the parser makes of the script
+    Fmt,     // Lay the script out the one way it should be
+    Explain, // The long version of an error code
+    Repl,    // Type things in and see what happens
+    Lsp,     // Language server for editors, over stdin and stdout
+    Debug,   // Run the script a statement at a time from the terminal
+    Dap,     // Debug adapter for editors, over stdin and stdout
+    Help,
+}
+
+impl Command {
+    fn from_name(name: &str) -> Option<Command> {
+        match name {
+            "run" => Some(Command::Run),
+            "check" => Some(Command::Check),
+            "tokens" => Some(Command::Tokens),
+            "ast" => Some(Command::Ast),
+            "fmt" => Some(Command::Fmt),
+            "explain" => Some(Command::Explain),
+            "repl" => Some(Command::Repl),
+            "lsp" => Some(Command::Lsp),
+            "debug" => Some(Command::Debug),
+            "dap" => Some(Command::Dap),
+            "help" => Some(Command::Help),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub command: Command,
+    pub target: Option<String>, // The script, or the code for explain
+    pub script_args: Vec<String>,
+    pub strict: bool,           // Conditions that aren't sigma or ohio are an error
+    pub entry: Option<String>,  // Runs instead of fein
+    pub allowed: Vec<LintKind>, // Lints that aren't reported
+    pub quiet: bool,            // Only errors, no warnings or hints
+    pub json: bool,             // tokens and ast as JSON
+    pub check: bool,            // fmt only says if the script would change
+}
+
+pub fn usage(program: &str) -> String {
+    format!(
+        "\
+Usage: {0} [command] [flags] <script> [args...]
+
+Commands:
+    run        check the script and run it, the default
+    check      parse, typecheck and lint without running anything
+    tokens     print the tokens the lexer makes
+    ast        print the syntax tree the parser makes
+    fmt        lay the script out the standard way, in place
+    explain    say more about an error code, like {0} explain E0001
+    repl       type things in and see what happens, with a script its cookables are loaded
+    lsp        run the language server editors talk to over stdin and stdout
+    debug      run the script under the debugger, with breakpoints and stepping
+    dap        run the debug adapter editors talk to over stdin and stdout
+    help       show this
+
+Flags:
+    --entry <function>       run this instead of fein
+    --strict                 conditions have to be sigma or ohio
+    -A, --allow <lint>       don't report this lint
+    -q, --quiet              only report errors
+    --json                   print tokens and ast as JSON
+    --check                  fmt changes nothing, it fails if the script isn't formatted",
+        program
+    )
+}
+
+// For run and debug, flags come before the script and everything after it is handed to the script.
+// The other commands take flags anywhere
+pub fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        command: Command::Run,
+        target: None,
+        script_args: Vec::new(),
+        strict: false,
+        entry: None,
+        allowed: Vec::new(),
+        quiet: false,
+        json: false,
+        check: false,
+    };
+
+    let mut rest = args.iter().skip(1).peekable();
+
+    if let Some(command) = rest.peek().and_then(|arg| Command::from_name(arg)) {
+        options.command = command;
+        rest.next();
+    }
+
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--strict" => options.strict = true,
+            "--quiet" | "-q" => options.quiet = true,
+            "--json" => options.json = true,
+            "--check" => options.check = true,
+            "--help" | "-h" => options.command = Command::Help,
+            "--entry" => match rest.next() {
+                Some(name) => options.entry = Some(name.clone()),
+                None => return Err("--entry needs the name of a function".to_string()),
+            },
+            "--allow" | "-A" => {
+                let name = rest
+                    .next()
+                    .ok_or_else(|| format!("{} needs the name of a lint", arg))?;
+
+                match LintKind::from_name(name) {
+                    Some(kind) => options.allowed.push(kind),
+                    None => {
+                        let names: Vec<&str> = LintKind::ALL.iter().map(|kind| kind.name()).collect();
+
+                        return Err(format!(
+                            "unknown lint {}, the lints are: {}",
+                            name,
+                            names.join(", ")
+                        ));
+                    }
+                }
+            }
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(format!("unknown flag {}", flag));
+            }
+            _ if options.target.is_none() => {
+                options.target = Some(arg.clone());
+
+                if matches!(options.command, Command::Run | Command::Debug) {
+                    break;
+                }
+            }
+            _ => return Err(format!("unexpected argument {}", arg)),
+        }
+    }
+
+    options.script_args = rest.cloned().collect();
+
+    // With nothing at all there's nothing to run, so it's the repl
+    if args.len() == 1 {
+        options.command = Command::Repl;
+    }
+
+    let needs_target = !matches!(
+        options.command,
+        Command::Help | Command::Repl | Command::Lsp | Command::Dap
+    );
+
+    if options.target.is_none() && needs_target {
+        return Err(match options.command {
+            Command::Explain => "explain needs an error code".to_string(),
+            _ => "no script given".to_string(),
+        });
+    }
+
+    Ok(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(command_line: &str) -> Result<Options, String> {
+        let args: Vec<String> = command_line.split_whitespace().map(String::from).collect();
+
+        parse_args(&args)
+    }
+
+    #[test]
+    fn run_is_the_default() {
+        let options = parse("skibidyscript game.skibidi").unwrap();
+
+        assert_eq!(options.command, Command::Run);
+        assert_eq!(options.target.as_deref(), Some("game.skibidi"));
+    }
+
+    #[test]
+    fn everything_after_the_script_is_the_scripts() {
+        let options = parse("skibidyscript --strict game.skibidi --strict 1").unwrap();
+
+        assert!(options.strict);
+        assert_eq!(options.script_args, vec!["--strict", "1"]);
+        assert_eq!(parse("skibidyscript game.skibidi --help").unwrap().command, Command::Run);
+    }
+
+    #[test]
+    fn other_commands_take_flags_anywhere() {
+        let options = parse("skibidyscript fmt game.skibidi --check").unwrap();
+        assert_eq!(options.command, Command::Fmt);
+        assert!(options.check);
+
+        let options = parse("skibidyscript check -A unused_variable game.skibidi -q").unwrap();
+        assert_eq!(options.allowed, vec![LintKind::UnusedVariable]);
+        assert!(options.quiet);
+
+        assert_eq!(parse("skibidyscript check a.skibidi b.skibidi").unwrap_err(), "unexpected argument b.skibidi");
+    }
+
+    #[test]
+    fn mistakes() {
+        assert_eq!(parse("skibidyscript check").unwrap_err(), "no script given");
+        assert_eq!(parse("skibidyscript explain").unwrap_err(), "explain needs an error code");
+        assert_eq!(parse("skibidyscript --nope game.skibidi").unwrap_err(), "unknown flag --nope");
+        assert_eq!(parse("skibidyscript --entry").unwrap_err(), "--entry needs the name of a function");
+        assert!(parse("skibidyscript -A nope game.skibidi").unwrap_err().starts_with("unknown lint nope"));
+    }
+
+    #[test]
+    fn nothing_at_all_is_the_repl() {
+        assert_eq!(parse("skibidyscript").unwrap().command, Command::Repl);
+        assert_eq!(parse("skibidyscript lsp").unwrap().command, Command::Lsp);
+    }
+}
@@ -0,0 +1,498 @@
+// dap.rs
+
+// `skibidyscript dap`, a debug adapter so editors can run a script under the debugger. It speaks
+// the Debug Adapter Protocol over stdin and stdout, framed like the language server's messages.
+//
+// A thread reads the editor's messages and hands them over. Until configurationDone they're
+// answered here, then the script runs with an Adapter as the interpreter's hook, which answers
+// them between statements and sits waiting on them while it's stopped. yap and errors go to the
+// editor as output events, stdout is taken. There's one thread as far as the editor knows.
+//
+// Frames and variable scopes only live while stopped, so their ids are just positions: frame ids
+// are 1 up from the innermost, and a scope's variablesReference is its frame id * 10 + the scope.
+
+use std::{
+    collections::HashSet,
+    fs, io,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        mpsc::{self, Receiver, TryRecvError},
+    },
+    thread,
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    cli,
+    debugger::{self, Frame, Scope, Step, Stepper},
+    interpreter::{Hook, Interpreter},
+    lexer::Lexer,
+    lsp::{read_message, send},
+    parser::{Parser, Stmt},
+    Script,
+};
+
+const THREAD_ID: i64 = 1;
+
+const SCOPES: [Scope; 3] = [Scope::Locals, Scope::Goat, Scope::Globals];
+
+// Every message the adapter sends gets the next number
+static SEQ: AtomicI64 = AtomicI64::new(1);
+
+fn message(mut message: Value) {
+    message["seq"] = json!(SEQ.fetch_add(1, Ordering::SeqCst));
+    send(message);
+}
+
+fn event(name: &str, body: Value) {
+    message(json!({ "type": "event", "event": name, "body": body }));
+}
+
+fn respond(request: &Value, body: Value) {
+    message(json!({
+        "type": "response",
+        "request_seq": request["seq"],
+        "success": true,
+        "command": request["command"],
+        "body": body,
+    }));
+}
+
+fn fail(request: &Value, text: &str) {
+    message(json!({
+        "type": "response",
+        "request_seq": request["seq"],
+        "success": false,
+        "command": request["command"],
+        "message": text,
+    }));
+}
+
+// yap, for Interpreter.output. writeln! writes in pieces, so it's sent a line at a time
+#[derive(Default)]
+struct Output {
+    line: Vec<u8>,
+}
+
+impl io::Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.line.extend_from_slice(buf);
+
+        if buf.contains(&b'\n') {
+            self.flush()?;
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.line.is_empty() {
+            let output = String::from_utf8_lossy(&self.line).to_string();
+            event("output", json!({ "category": "stdout", "output": output }));
+            self.line.clear();
+        }
+
+        Ok(())
+    }
+}
+
+// A yap without a newline at the end still shows up
+impl Drop for Output {
+    fn drop(&mut self) {
+        let _ = io::Write::flush(self);
+    }
+}
+
+// Errors and hints, for Script.errors
+fn errors(text: &str) {
+    event("output", json!({ "category": "stderr", "output": text }));
+}
+
+// What the launch configuration asked for
+struct Launch {
+    program: String,
+    source: String,
+    args: Vec<String>,
+    entry: Option<String>,
+    stop_on_entry: bool,
+}
+
+pub fn run(program: &str) -> i32 {
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        let stdin = io::stdin();
+        let mut input = stdin.lock();
+
+        while let Ok(Some(message)) = read_message(&mut input) {
+            if sender.send(message).is_err() {
+                break;
+            }
+        }
+    });
+
+    let messages = Rc::new(receiver);
+    let mut launch = None;
+    let mut breakpoints = HashSet::new();
+
+    // Setting up, nothing's running yet
+    let Launch {
+        program: path,
+        source,
+        args,
+        entry,
+        stop_on_entry,
+    } = loop {
+        let request = match messages.recv() {
+            Ok(request) => request,
+            Err(_) => return 1,
+        };
+        let arguments = &request["arguments"];
+
+        match request["command"].as_str().unwrap_or_default() {
+            "initialize" => respond(
+                &request,
+                json!({ "supportsConfigurationDoneRequest": true, "supportsEvaluateForHovers": true }),
+            ),
+            "launch" => {
+                let path = arguments["program"].as_str().unwrap_or_default();
+
+                let source = match fs::read_to_string(path) {
+                    Ok(source) => source,
+                    Err(e) => {
+                        fail(&request, &format!("couldn't read {}: {}", path, e));
+                        continue;
+                    }
+                };
+
+                launch = Some(Launch {
+                    program: path.to_string(),
+                    source,
+                    args: arguments["args"]
+                        .as_array()
+                        .map(|args| args.iter().filter_map(|arg| arg.as_str().map(String::from)).collect())
+                        .unwrap_or_default(),
+                    entry: arguments["entry"].as_str().map(String::from),
+                    stop_on_entry: arguments["stopOnEntry"].as_bool().unwrap_or(false),
+                });
+
+                respond(&request, json!({}));
+                // Ready for breakpoints now there's a script to put them in
+                event("initialized", json!({}));
+            }
+            "setBreakpoints" => {
+                let source = launch.as_ref().map_or("", |launch| launch.source.as_str());
+                set_breakpoints(&request, source, &mut breakpoints);
+            }
+            "setExceptionBreakpoints" => respond(&request, json!({ "breakpoints": [] })),
+            "threads" => respond(&request, json!({ "threads": [] })),
+            "configurationDone" => {
+                respond(&request, json!({}));
+
+                match launch.take() {
+                    Some(launch) => break launch,
+                    None => {
+                        errors("error: configurationDone before launch, there's nothing to run\n");
+                        return 1;
+                    }
+                }
+            }
+            "disconnect" | "terminate" => {
+                respond(&request, json!({}));
+
+                return 0;
+            }
+            command => fail(&request, &format!("{} isn't something the adapter can do yet", command)),
+        }
+    };
+
+    // The same command line `skibidyscript run` would get
+    let mut command_line = vec![program.to_string()];
+    if let Some(entry) = entry {
+        command_line.extend(["--entry".to_string(), entry]);
+    }
+    command_line.push(path.clone());
+    command_line.extend(args);
+
+    let options = match cli::parse_args(&command_line) {
+        Ok(options) => options,
+        Err(message) => {
+            errors(&format!("error: {}\n", message));
+
+            return 1;
+        }
+    };
+
+    let mut stepper = Stepper::new(stop_on_entry);
+    stepper.breakpoints = breakpoints;
+
+    let mut interpreter = Interpreter::new();
+    interpreter.output = Box::<Output>::default();
+    interpreter.stdin = false;
+    interpreter.hook = Some(Box::new(Adapter {
+        stepper,
+        messages: messages.clone(),
+        source: source.clone(),
+        path: path.clone(),
+    }));
+
+    let script = Script {
+        program: program.to_string(),
+        filename: path,
+        source,
+        options,
+        errors,
+    };
+
+    let code = script.run(interpreter);
+
+    event("exited", json!({ "exitCode": code }));
+    event("terminated", json!({}));
+
+    // The editor says when it's done
+    while let Ok(request) = messages.recv() {
+        match request["command"].as_str().unwrap_or_default() {
+            "disconnect" | "terminate" => {
+                respond(&request, json!({}));
+
+                return 0;
+            }
+            "threads" => respond(&request, json!({ "threads": [] })),
+            _ => fail(&request, "the script has finished"),
+        }
+    }
+
+    0
+}
+
+// Breakpoints are set a whole file at a time. One on a line without any code can't be hit,
+// so it's not verified
+fn set_breakpoints(request: &Value, source: &str, breakpoints: &mut HashSet<usize>) {
+    let code = code_lines(source);
+
+    breakpoints.clear();
+    let mut results = Vec::new();
+
+    for breakpoint in request["arguments"]["breakpoints"].as_array().into_iter().flatten() {
+        let line = breakpoint["line"].as_u64().unwrap_or_default() as usize;
+        let verified = code.contains(&line);
+
+        if verified {
+            breakpoints.insert(line);
+        }
+
+        results.push(json!({ "verified": verified, "line": line }));
+    }
+
+    respond(request, json!({ "breakpoints": results }));
+}
+
+fn code_lines(source: &str) -> HashSet<usize> {
+    let mut lexer = Lexer::new(source);
+
+    match Parser::new(&mut lexer) {
+        Ok(mut parser) => debugger::stop_lines(&parser.parse().0),
+        Err(_) => HashSet::new(),
+    }
+}
+
+// The hook while the script runs
+struct Adapter {
+    stepper: Stepper,
+    messages: Rc<Receiver<Value>>,
+    source: String,
+    path: String,
+}
+
+impl Adapter {
+    // Requests that can come at any time. Some(step) once a stop is over
+    fn request(&mut self, request: &Value, interpreter: &mut Interpreter, frames: Option<&[Frame]>) -> Option<Step> {
+        let step = match request["command"].as_str().unwrap_or_default() {
+            "continue" => Step::Continue,
+            "next" => Step::Over,
+            "stepIn" => Step::In,
+            "stepOut" => Step::Out,
+            "pause" => {
+                // Already stopped if there are frames
+                if frames.is_none() {
+                    self.stepper.pause();
+                }
+                respond(request, json!({}));
+
+                return None;
+            }
+            "setBreakpoints" => {
+                set_breakpoints(request, &self.source, &mut self.stepper.breakpoints);
+
+                return None;
+            }
+            "threads" => {
+                respond(request, json!({ "threads": [{ "id": THREAD_ID, "name": "main" }] }));
+
+                return None;
+            }
+            "disconnect" | "terminate" => {
+                respond(request, json!({}));
+
+                std::process::exit(0);
+            }
+            command => {
+                match frames {
+                    Some(frames) => self.inspect(command, request, interpreter, frames),
+                    None => fail(request, &format!("the script has to be stopped for {}", command)),
+                }
+
+                return None;
+            }
+        };
+
+        respond(request, json!({ "allThreadsContinued": true }));
+
+        // A continue that comes while it's running has nothing to change
+        frames.is_some().then_some(step)
+    }
+
+    // Requests that look around while stopped
+    fn inspect(&mut self, command: &str, request: &Value, interpreter: &mut Interpreter, frames: &[Frame]) {
+        let arguments = &request["arguments"];
+
+        match command {
+            "stackTrace" => {
+                let name = std::path::Path::new(&self.path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+
+                let stack: Vec<Value> = frames
+                    .iter()
+                    .enumerate()
+                    .map(|(i, frame)| {
+                        json!({
+                            "id": i + 1,
+                            "name": frame.name,
+                            "line": frame.span.line,
+                            "column": frame.span.column,
+                            "source": { "name": name, "path": self.path },
+                        })
+                    })
+                    .collect();
+
+                respond(request, json!({ "stackFrames": stack, "totalFrames": stack.len() }));
+            }
+            "scopes" => {
+                let frame = arguments["frameId"].as_u64().unwrap_or_default() as usize;
+
+                // The top level has no locals, goat is only for methods and only the innermost
+                // frame knows which pookie
+                let has_locals = frames
+                    .get(frame.wrapping_sub(1))
+                    .is_some_and(|frame| frame.locals.is_some());
+                let has_goat = frame == 1 && interpreter.current_instance.is_some();
+
+                let scopes: Vec<Value> = SCOPES
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, scope)| match scope {
+                        Scope::Locals => has_locals,
+                        Scope::Goat => has_goat,
+                        Scope::Globals => true,
+                    })
+                    .map(|(i, scope)| {
+                        json!({
+                            "name": scope.name(),
+                            "variablesReference": frame * 10 + i + 1,
+                            "expensive": false,
+                        })
+                    })
+                    .collect();
+
+                respond(request, json!({ "scopes": scopes }));
+            }
+            "variables" => {
+                let reference = arguments["variablesReference"].as_u64().unwrap_or_default() as usize;
+
+                let variables: Vec<Value> = match (reference / 10, SCOPES.get((reference % 10).wrapping_sub(1))) {
+                    (frame, Some(scope)) if frame > 0 => debugger::variables(interpreter, frames, frame - 1, *scope)
+                        .into_iter()
+                        .map(|(name, value)| json!({ "name": name, "value": value, "variablesReference": 0 }))
+                        .collect(),
+                    _ => Vec::new(),
+                };
+
+                respond(request, json!({ "variables": variables }));
+            }
+            // Always in the innermost frame, whichever one the editor has picked
+            "evaluate" => match debugger::evaluate(interpreter, arguments["expression"].as_str().unwrap_or_default()) {
+                Ok(result) => respond(request, json!({ "result": result, "variablesReference": 0 })),
+                Err(message) => fail(request, &message),
+            },
+            command => fail(request, &format!("{} isn't something the adapter can do yet", command)),
+        }
+    }
+}
+
+impl Hook for Adapter {
+    fn before_statement(&mut self, interpreter: &mut Interpreter, stmt: &Stmt) {
+        // Anything the editor sent while the script was running, a pause or new breakpoints
+        loop {
+            match self.messages.try_recv() {
+                Ok(request) => {
+                    self.request(&request, interpreter, None);
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => std::process::exit(1),
+            }
+        }
+
+        let reason = match self.stepper.should_stop(interpreter, stmt) {
+            Some(reason) => reason,
+            None => return,
+        };
+
+        let frames = debugger::stack(interpreter, stmt.span());
+        event(
+            "stopped",
+            json!({ "reason": reason.name(), "threadId": THREAD_ID, "allThreadsStopped": true }),
+        );
+
+        loop {
+            let request = match self.messages.recv() {
+                Ok(request) => request,
+                // The editor went away
+                Err(_) => std::process::exit(1),
+            };
+
+            if let Some(step) = self.request(&request, interpreter, Some(&frames)) {
+                self.stepper.resume(step, interpreter);
+
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_lines_with_statements_take_breakpoints() {
+        let source = "\
+cookable fein()
+    x is 1
+
+    sus (x) eat
+        cook yap(x)
+    cap
+        blud 2
+    slay
+slay
+";
+        let mut lines: Vec<usize> = code_lines(source).into_iter().collect();
+        lines.sort();
+
+        assert_eq!(lines, vec![2, 4, 5, 7]);
+    }
+}
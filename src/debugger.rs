@@ -0,0 +1,601 @@
+// debugger.rs
+
+// Stepping through a script a statement at a time. The interpreter runs its hook before every
+// statement, and the debugger decides in there whether to stop: at a breakpoint, or because it
+// was asked to step. While it's stopped the script waits, and the variables can be looked at.
+//
+// The stopping and looking around is shared, `skibidyscript debug` drives it from the terminal
+// with Terminal down below, and dap.rs drives it from an editor.
+
+use rustyline::{error::ReadlineError, DefaultEditor};
+use std::collections::HashSet;
+
+use crate::{
+    interpreter::{Hook, Interpreter},
+    lexer::{Lexer, Span},
+    parser::{Parser, Stmt},
+};
+
+// What to do after a stop
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Step {
+    Continue, // Until a breakpoint
+    In,       // The next statement, wherever it is
+    Over,     // The next statement in this cookable, or the one it goes back to
+    Out,      // The next statement after this cookable gives back
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StopReason {
+    Entry,
+    Breakpoint,
+    Step,
+    Pause,
+}
+
+impl StopReason {
+    // The names DAP uses
+    pub fn name(&self) -> &'static str {
+        match self {
+            StopReason::Entry => "entry",
+            StopReason::Breakpoint => "breakpoint",
+            StopReason::Step => "step",
+            StopReason::Pause => "pause",
+        }
+    }
+}
+
+// Breakpoints, and what was asked for at the last stop
+pub struct Stepper {
+    pub breakpoints: HashSet<usize>, // Lines
+    step: Step,
+    depth: usize,                    // How many cookables deep the last stop was
+    pending: Option<StopReason>,     // Stop at the next statement no matter what, for entry and pause
+    previous: Option<(Span, usize)>, // The last statement and its depth, so a line only stops once
+}
+
+impl Stepper {
+    pub fn new(stop_on_entry: bool) -> Self {
+        Stepper {
+            breakpoints: HashSet::new(),
+            step: Step::Continue,
+            depth: 0,
+            pending: stop_on_entry.then_some(StopReason::Entry),
+            previous: None,
+        }
+    }
+
+    pub fn should_stop(&mut self, interpreter: &Interpreter, stmt: &Stmt) -> Option<StopReason> {
+        if is_declaration(stmt) {
+            return None;
+        }
+
+        let span = stmt.span();
+        let depth = interpreter.calls.len();
+
+        // Further along the line the last statement was on, like the blud in `sus(c) eat blud 0 slay`.
+        // A loop coming back round to the same statement is another go, so that stops again
+        let same_line = self.previous.is_some_and(|(previous, previous_depth)| {
+            previous.line == span.line && previous.column < span.column && previous_depth == depth
+        });
+        self.previous = Some((span, depth));
+
+        if let Some(reason) = self.pending.take() {
+            return Some(reason);
+        }
+
+        let stepped = match self.step {
+            Step::Continue => false,
+            Step::In => true,
+            Step::Over => depth <= self.depth,
+            Step::Out => depth < self.depth,
+        };
+
+        if stepped {
+            Some(StopReason::Step)
+        } else if self.breakpoints.contains(&span.line) && !same_line {
+            Some(StopReason::Breakpoint)
+        } else {
+            None
+        }
+    }
+
+    // Called on the way out of a stop
+    pub fn resume(&mut self, step: Step, interpreter: &Interpreter) {
+        self.step = step;
+        self.depth = interpreter.calls.len();
+    }
+
+    pub fn pause(&mut self) {
+        self.pending = Some(StopReason::Pause);
+    }
+}
+
+// Declaring a cookable or a pookie isn't anything to stop at
+fn is_declaration(stmt: &Stmt) -> bool {
+    matches!(stmt, Stmt::Function { .. } | Stmt::Class { .. } | Stmt::Enum { .. })
+}
+
+// The lines a breakpoint can stop on, the ones a statement starts on. A line with only a slay or
+// an eat on it never gets a statement of its own
+pub fn stop_lines(statements: &[Stmt]) -> HashSet<usize> {
+    let mut lines = HashSet::new();
+
+    for stmt in statements {
+        if !is_declaration(stmt) {
+            lines.insert(stmt.span().line);
+        }
+
+        match stmt {
+            Stmt::Function { body, .. } | Stmt::While { body, .. } | Stmt::ForLoop { body, .. } => {
+                lines.extend(stop_lines(body));
+            }
+            Stmt::Class { functions, .. } => lines.extend(stop_lines(functions)),
+            Stmt::If {
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                lines.extend(stop_lines(then_branch));
+                lines.extend(stop_lines(else_branch.as_deref().unwrap_or_default()));
+            }
+            Stmt::Match { arms, .. } => {
+                for arm in arms {
+                    lines.extend(stop_lines(&arm.body));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    lines
+}
+
+pub struct Frame {
+    pub name: String,
+    pub span: Span,            // Where it's at right now
+    pub locals: Option<usize>, // Its parameters in interpreter.locals, the top level has none
+}
+
+// Innermost first, `span` is the statement it stopped at
+pub fn stack(interpreter: &Interpreter, span: Span) -> Vec<Frame> {
+    let calls = &interpreter.calls;
+    let mut frames = Vec::new();
+
+    for (i, call) in calls.iter().enumerate().rev() {
+        let name = match &call.class {
+            Some(class) => format!("{}.{}", class, call.function),
+            None => call.function.clone(),
+        };
+
+        frames.push(Frame {
+            name,
+            span: calls.get(i + 1).map_or(span, |inner| inner.call_site),
+            locals: Some(i),
+        });
+    }
+
+    // The top level, which has finished by the time the entry function is cooked from nowhere
+    let top = calls.first().map_or(span, |call| call.call_site);
+    if top != Span::default() {
+        frames.push(Frame {
+            name: "<script>".to_string(),
+            span: top,
+            locals: None,
+        });
+    }
+
+    frames
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scope {
+    Locals,
+    Goat, // Fields of the pookie a method was cooked on
+    Globals,
+}
+
+impl Scope {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scope::Locals => "locals",
+            Scope::Goat => "goat",
+            Scope::Globals => "globals",
+        }
+    }
+}
+
+// Names and their values, sorted by name. goat only has something for the innermost frame,
+// that's the only one current_instance is right for
+pub fn variables(interpreter: &mut Interpreter, frames: &[Frame], frame: usize, scope: Scope) -> Vec<(String, String)> {
+    let values = match scope {
+        Scope::Locals => frames
+            .get(frame)
+            .and_then(|frame| frame.locals)
+            .and_then(|i| interpreter.locals.get(i))
+            .cloned()
+            .unwrap_or_default(),
+        Scope::Goat if frame == 0 => interpreter
+            .current_instance
+            .as_ref()
+            .and_then(|id| interpreter.instances.get(id))
+            .map(|instance| instance.variables.clone())
+            .unwrap_or_default(),
+        Scope::Goat => Default::default(),
+        Scope::Globals => interpreter.variables.clone(),
+    };
+
+    let mut variables: Vec<(String, String)> = values
+        .into_iter()
+        .map(|(name, value)| {
+            let text = interpreter
+                .format_value(value, true)
+                .unwrap_or_else(|e| format!("<{}>", e.message()));
+
+            (name, text)
+        })
+        .collect();
+    variables.sort();
+
+    variables
+}
+
+// An expression typed in while stopped. It runs for real, so a cook in there happens
+pub fn evaluate(interpreter: &mut Interpreter, text: &str) -> Result<String, String> {
+    let mut lexer = Lexer::new(text);
+    let expr = Parser::new(&mut lexer)
+        .and_then(|mut parser| parser.parse_lone_expression())
+        .map_err(|e| e.message())?;
+
+    // Errors in the script should still point at the script, not at what was typed
+    let span = interpreter.span;
+    let result = match interpreter.evaluate_expression(expr) {
+        Ok(value) => interpreter.format_value(value, true).map_err(|e| e.message()),
+        Err(e) => Err(e.message()),
+    };
+    interpreter.span = span;
+
+    result
+}
+
+const HELP: &str = "\
+    c, continue      run until a breakpoint
+    s, step          the next statement, into cookables
+    n, next          the next statement, over cookables
+    o, out           until this cookable gives back
+    b, break <line>  stop at a line, without one lists the breakpoints
+    d, delete <line> stop stopping at a line
+    l, list          the source around here
+    p, print <expr>  what an expression comes out as
+    locals           the parameters of this cookable
+    goat             the fields of the pookie this method was cooked on
+    globals          the variables at the top level
+    v, vars          all three
+    bt, stack        the cookables that got here
+    q, quit          stop the script
+    h, help          show this
+An empty line does the last command again.";
+
+// `skibidyscript debug`, stops before the first statement and reads commands from the terminal
+pub struct Terminal {
+    stepper: Stepper,
+    lines: Vec<String>,
+    editor: Option<DefaultEditor>,
+    last_command: String,
+}
+
+impl Terminal {
+    pub fn new(source: &str) -> Self {
+        Terminal {
+            stepper: Stepper::new(true),
+            lines: source.lines().map(String::from).collect(),
+            editor: DefaultEditor::new().ok(),
+            last_command: String::new(),
+        }
+    }
+
+    fn read_command(&mut self) -> Option<String> {
+        let line = match &mut self.editor {
+            Some(editor) => match editor.readline("(debug) ") {
+                Ok(line) => line,
+                // ctrl-c gives a fresh prompt, ctrl-d quits
+                Err(ReadlineError::Interrupted) => String::new(),
+                Err(_) => return None,
+            },
+            None => {
+                let mut line = String::new();
+                match std::io::stdin().read_line(&mut line) {
+                    Ok(0) | Err(_) => return None,
+                    Ok(_) => line,
+                }
+            }
+        };
+
+        let line = line.trim().to_string();
+        if line.is_empty() {
+            return Some(self.last_command.clone());
+        }
+
+        if let Some(editor) = &mut self.editor {
+            let _ = editor.add_history_entry(line.as_str());
+        }
+        self.last_command = line.clone();
+
+        Some(line)
+    }
+
+    fn show_line(&self, line: usize, current: usize) {
+        if let Some(text) = self.lines.get(line.wrapping_sub(1)) {
+            let marker = match (line == current, self.stepper.breakpoints.contains(&line)) {
+                (true, _) => ">",
+                (false, true) => "*",
+                (false, false) => " ",
+            };
+
+            println!("{} {:>4} | {}", marker, line, text);
+        }
+    }
+
+    fn show_variables(&self, interpreter: &mut Interpreter, frames: &[Frame], scope: Scope) {
+        let variables = variables(interpreter, frames, 0, scope);
+        if variables.is_empty() {
+            println!("no {}", scope.name());
+        }
+
+        for (name, value) in variables {
+            println!("{} is {}", name, value);
+        }
+    }
+}
+
+impl Hook for Terminal {
+    fn before_statement(&mut self, interpreter: &mut Interpreter, stmt: &Stmt) {
+        let reason = match self.stepper.should_stop(interpreter, stmt) {
+            Some(reason) => reason,
+            None => return,
+        };
+
+        let span = stmt.span();
+        let frames = stack(interpreter, span);
+        let function = frames.first().map_or("<script>", |frame| frame.name.as_str());
+
+        match reason {
+            StopReason::Entry => println!("stopped before the first statement, h lists the commands"),
+            StopReason::Breakpoint => println!("breakpoint at line {} in {}", span.line, function),
+            _ => println!("line {} in {}", span.line, function),
+        }
+        self.show_line(span.line, span.line);
+
+        loop {
+            let command = match self.read_command() {
+                Some(command) => command,
+                None => std::process::exit(0),
+            };
+
+            let (name, rest) = command.split_once(' ').unwrap_or((command.as_str(), ""));
+            let rest = rest.trim();
+
+            let step = match name {
+                "" => continue,
+                "c" | "continue" => Step::Continue,
+                "s" | "step" => Step::In,
+                "n" | "next" => Step::Over,
+                "o" | "out" => Step::Out,
+                "b" | "break" if rest.is_empty() => {
+                    let mut lines: Vec<&usize> = self.stepper.breakpoints.iter().collect();
+                    lines.sort();
+
+                    if lines.is_empty() {
+                        println!("no breakpoints");
+                    }
+                    for line in lines {
+                        self.show_line(*line, 0);
+                    }
+                    continue;
+                }
+                "b" | "break" | "d" | "delete" => {
+                    match rest.parse::<usize>() {
+                        Ok(line) if name.starts_with('b') && (line == 0 || line > self.lines.len()) => {
+                            println!("the script only has {} lines", self.lines.len());
+                        }
+                        Ok(line) if name.starts_with('b') => {
+                            self.stepper.breakpoints.insert(line);
+                        }
+                        Ok(line) => {
+                            if !self.stepper.breakpoints.remove(&line) {
+                                println!("there's no breakpoint at line {}", line);
+                            }
+                        }
+                        Err(_) => println!("{} needs a line number", name),
+                    }
+                    continue;
+                }
+                "l" | "list" => {
+                    for line in span.line.saturating_sub(5).max(1)..=span.line + 5 {
+                        self.show_line(line, span.line);
+                    }
+                    continue;
+                }
+                "p" | "print" => {
+                    match evaluate(interpreter, rest) {
+                        Ok(text) => println!("{}", text),
+                        Err(message) => println!("error: {}", message),
+                    }
+                    continue;
+                }
+                "locals" => {
+                    self.show_variables(interpreter, &frames, Scope::Locals);
+                    continue;
+                }
+                "goat" => {
+                    self.show_variables(interpreter, &frames, Scope::Goat);
+                    continue;
+                }
+                "globals" => {
+                    self.show_variables(interpreter, &frames, Scope::Globals);
+                    continue;
+                }
+                "v" | "vars" => {
+                    for scope in [Scope::Locals, Scope::Goat, Scope::Globals] {
+                        println!("{}:", scope.name());
+                        self.show_variables(interpreter, &frames, scope);
+                    }
+                    continue;
+                }
+                "bt" | "stack" => {
+                    for frame in &frames {
+                        println!("  {} at line {}", frame.name, frame.span.line);
+                    }
+                    continue;
+                }
+                "q" | "quit" => std::process::exit(0),
+                "h" | "help" => {
+                    println!("{}", HELP);
+                    continue;
+                }
+                other => {
+                    println!("unknown command {}, h lists them", other);
+                    continue;
+                }
+            };
+
+            self.stepper.resume(step, interpreter);
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::RefCell, rc::Rc};
+
+    fn parse(source: &str) -> Vec<Stmt> {
+        let mut lexer = Lexer::new(source);
+        let (statements, errors) = Parser::new(&mut lexer).unwrap().parse();
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        statements
+    }
+
+    // Steps the way it's told and writes down the line of every stop, with what evaluate said there
+    struct Recorder {
+        stepper: Stepper,
+        steps: Vec<Step>,
+        stops: Rc<RefCell<Vec<(usize, String)>>>,
+    }
+
+    impl Hook for Recorder {
+        fn before_statement(&mut self, interpreter: &mut Interpreter, stmt: &Stmt) {
+            if self.stepper.should_stop(interpreter, stmt).is_none() {
+                return;
+            }
+
+            let x = evaluate(interpreter, "x").unwrap_or_else(|e| e);
+            self.stops.borrow_mut().push((stmt.span().line, x));
+
+            let step = if self.steps.is_empty() {
+                Step::Continue
+            } else {
+                self.steps.remove(0)
+            };
+            self.stepper.resume(step, interpreter);
+        }
+    }
+
+    const SOURCE: &str = "\
+cookable double(x)
+    y is x * 2
+    blud y
+slay
+
+x is 1
+x is cook double(x)
+x is cook double(x)
+";
+
+    fn stops(breakpoints: &[usize], steps: &[Step]) -> Vec<(usize, String)> {
+        let stops = Rc::new(RefCell::new(Vec::new()));
+        let mut stepper = Stepper::new(breakpoints.is_empty());
+        stepper.breakpoints.extend(breakpoints);
+
+        let mut interpreter = Interpreter::new();
+        interpreter.hook = Some(Box::new(Recorder {
+            stepper,
+            steps: steps.to_vec(),
+            stops: stops.clone(),
+        }));
+        interpreter.interpret(parse(SOURCE)).unwrap();
+
+        let stops = stops.borrow().clone();
+        stops
+    }
+
+    fn lines(stops: Vec<(usize, String)>) -> Vec<usize> {
+        stops.into_iter().map(|(line, _)| line).collect()
+    }
+
+    #[test]
+    fn stepping() {
+        assert_eq!(lines(stops(&[], &[Step::Over, Step::Over, Step::Over])), vec![6, 7, 8]);
+        assert_eq!(
+            lines(stops(&[], &[Step::Over, Step::In, Step::In, Step::In])),
+            vec![6, 7, 2, 3, 8]
+        );
+        assert_eq!(lines(stops(&[], &[Step::Over, Step::In, Step::Out])), vec![6, 7, 2, 8]);
+    }
+
+    #[test]
+    fn breakpoints_stop_every_time() {
+        assert_eq!(lines(stops(&[3], &[])), vec![3, 3]);
+    }
+
+    #[test]
+    fn evaluate_sees_the_frame_it_stopped_in() {
+        let stops = stops(&[2, 8], &[]);
+
+        // Line 8 stops before its cook runs, x is still what line 7 made it
+        assert_eq!(
+            stops,
+            vec![(2, "1".to_string()), (8, "2".to_string()), (2, "2".to_string())]
+        );
+    }
+
+    #[test]
+    fn stop_lines_go_into_pookies_and_vibechecks() {
+        let statements = parse(
+            "\
+pookie P()
+    cookable __edge__()
+        goat.x is 1
+    slay
+slay
+
+vibecheck (1) eat
+    1 eat
+        cook yap(1)
+    slay
+slay
+",
+        );
+        let mut lines: Vec<usize> = stop_lines(&statements).into_iter().collect();
+        lines.sort();
+
+        assert_eq!(lines, vec![3, 7, 9]);
+    }
+
+    #[test]
+    fn breakpoints_skip_declarations() {
+        let statements = parse("cookable f()\n    blud 1\nslay\nx is 1\n");
+        let interpreter = Interpreter::new();
+
+        let mut stepper = Stepper::new(false);
+        stepper.breakpoints.extend([1, 4]);
+
+        assert_eq!(stepper.should_stop(&interpreter, &statements[0]), None);
+        assert_eq!(
+            stepper.should_stop(&interpreter, &statements[1]),
+            Some(StopReason::Breakpoint)
+        );
+    }
+}
@@ -0,0 +1,362 @@
+// diagnostic.rs
+
+// Errors for people to read. error.rs says what went wrong, this shows where it went wrong,
+// with the line from the script and carets under the exact spot:
+//
+// error[E0001]: expected `(`, but found `x`
+//  --> examples/hello.skibidi:2:14
+//   |
+// 2 |     cook yap x
+//   |              ^ expected `(` here
+
+use std::io::IsTerminal;
+
+use crate::{
+    error::{self, ParseError, RuntimeError},
+    lexer::{Span, Token},
+    lint::Lint,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning, // From the lint pass, the script still runs
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: Option<&'static str>, // E0001, W0001 and so on
+    pub message: String,
+    pub span: Option<Span>,
+    pub label: Option<String>, // Next to the carets
+    pub notes: Vec<String>,
+    pub help: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn error(message: String, span: Option<Span>) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            code: None,
+            message,
+            span,
+            label: None,
+            notes: Vec::new(),
+            help: Vec::new(),
+        }
+    }
+
+    pub fn warning(message: String, span: Option<Span>) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            ..Diagnostic::error(message, span)
+        }
+    }
+
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    pub fn with_label(mut self, label: String) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    pub fn with_note(mut self, note: String) -> Self {
+        self.notes.push(note);
+        self
+    }
+
+    pub fn with_help(mut self, help: String) -> Self {
+        self.help.push(help);
+        self
+    }
+
+    pub fn render(&self, source: &str, filename: &str, color: bool) -> String {
+        let (title, accent) = match self.severity {
+            Severity::Error => ("error", RED),
+            Severity::Warning => ("warning", YELLOW),
+        };
+        let title = match self.code {
+            Some(code) => format!("{}[{}]: ", title, code),
+            None => format!("{}: ", title),
+        };
+
+        let mut out = format!(
+            "{}{}\n",
+            paint(&title, accent, color),
+            paint(&self.message, BOLD, color)
+        );
+
+        // Span::default() is used for "nowhere in particular"
+        let span = self.span.filter(|span| span.line > 0);
+        // The end of the file is on the line after the last newline, which lines() doesn't give back
+        let line_text = span.and_then(|span| {
+            source
+                .lines()
+                .nth(span.line - 1)
+                .or_else(|| (span.line == source.lines().count() + 1).then_some(""))
+        });
+
+        let gutter = match span {
+            Some(span) => " ".repeat(span.line.to_string().len()),
+            None => String::new(),
+        };
+        let bar = paint("|", BLUE, color);
+
+        if let (Some(span), Some(line_text)) = (span, line_text) {
+            out += &format!(
+                "{}{} {}:{}:{}\n",
+                gutter,
+                paint("-->", BLUE, color),
+                filename,
+                span.line,
+                span.column
+            );
+            out += &format!("{} {}\n", gutter, bar);
+            out += &format!(
+                "{} {} {}\n",
+                paint(&span.line.to_string(), BLUE, color),
+                bar,
+                line_text
+            );
+
+            // Tabs stay tabs, so the carets line up with the text above
+            let padding: String = line_text
+                .chars()
+                .take(span.column.saturating_sub(1))
+                .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+                .collect();
+
+            // Spans over more than one line get underlined to the end of the first one
+            let line_length = line_text.chars().count() + 1;
+            let end = if span.end_line == span.line {
+                span.end_column
+            } else {
+                line_length
+            };
+            let carets = "^".repeat(end.saturating_sub(span.column).max(1));
+
+            let mut underline = paint(&carets, accent, color);
+            if let Some(label) = &self.label {
+                underline += &format!(" {}", paint(label, accent, color));
+            }
+
+            out += &format!("{} {} {}{}\n", gutter, bar, padding, underline);
+        }
+
+        for note in &self.notes {
+            out += &format!("{} {} {}\n", gutter, paint("= note:", BOLD, color), indent(note, &gutter));
+        }
+
+        for help in &self.help {
+            out += &format!("{} {} {}\n", gutter, paint("= help:", BOLD, color), indent(help, &gutter));
+        }
+
+        out
+    }
+}
+
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+
+fn paint(text: &str, code: &str, color: bool) -> String {
+    if color {
+        format!("{}{}\x1b[0m", code, text)
+    } else {
+        text.to_string()
+    }
+}
+
+// Notes that go over more than one line line up under the first one
+fn indent(text: &str, gutter: &str) -> String {
+    text.replace('\n', &format!("\n{}         ", gutter))
+}
+
+// Colour only when a person is looking, and NO_COLOR turns it off like everywhere else
+pub fn use_color(stream: &impl IsTerminal) -> bool {
+    std::env::var_os("NO_COLOR").is_none() && stream.is_terminal()
+}
+
+fn with_suggestion(diagnostic: Diagnostic, suggestion: &Option<String>) -> Diagnostic {
+    match suggestion {
+        Some(suggestion) => diagnostic.with_help(format!("did you mean `{}`?", suggestion)),
+        None => diagnostic,
+    }
+}
+
+impl From<&ParseError> for Diagnostic {
+    fn from(e: &ParseError) -> Self {
+        let diagnostic = Diagnostic::error(e.message(), e.span()).with_code(e.code());
+
+        match e {
+            ParseError::UnexpectedToken {
+                expected, found, ..
+            } => {
+                let diagnostic =
+                    diagnostic.with_label(format!("expected {} here", error::describe(expected)));
+
+                if *expected == Token::Slay && *found == Token::EOF {
+                    diagnostic.with_help("every block needs a `slay` to close it".to_string())
+                } else {
+                    diagnostic
+                }
+            }
+            ParseError::UnknownUnexpectedToken { .. } => {
+                diagnostic.with_label("not expected here".to_string())
+            }
+            ParseError::LexerUnexpectedChar { .. } => {
+                diagnostic.with_label("unexpected character".to_string())
+            }
+            ParseError::ArgumentMismatch { expected, .. } => {
+                diagnostic.with_label(format!("expected {} arguments", expected))
+            }
+            ParseError::ConstantReassign {
+                name, declared_at, ..
+            } => diagnostic
+                .with_label("reassigned here".to_string())
+                .with_note(format!("{} was declared based on {}", name, declared_at))
+                .with_help(format!("declare {} without `based` if it needs to change", name)),
+            ParseError::TypeMismatch { found, .. } => {
+                diagnostic.with_label(format!("found {}", found))
+            }
+            ParseError::UnknownType { suggestion, .. } => with_suggestion(
+                diagnostic.with_label("not a type, a pookie or a vibe".to_string()),
+                suggestion,
+            ),
+            ParseError::UnknownVariant { suggestion, .. } => with_suggestion(
+                diagnostic.with_label("no such variant".to_string()),
+                suggestion,
+            ),
+            ParseError::GeneralError { .. } | ParseError::Other(_) => diagnostic,
+        }
+    }
+}
+
+impl From<&Lint> for Diagnostic {
+    fn from(lint: &Lint) -> Self {
+        let diagnostic = Diagnostic::warning(lint.message.clone(), Some(lint.span))
+            .with_code(lint.kind.code())
+            .with_note(format!("turn this off with --allow {}", lint.kind.name()));
+
+        match &lint.help {
+            Some(help) => diagnostic.with_help(help.clone()),
+            None => diagnostic,
+        }
+    }
+}
+
+impl From<&RuntimeError> for Diagnostic {
+    fn from(e: &RuntimeError) -> Self {
+        let diagnostic = Diagnostic::error(e.message(), e.span()).with_code(e.code());
+
+        match e {
+            RuntimeError::Traceback { error, frames } => {
+                let trace = frames
+                    .iter()
+                    .rev()
+                    .map(|frame| format!("  {}", frame))
+                    .collect::<Vec<String>>()
+                    .join("\n");
+
+                Diagnostic::from(error.as_ref())
+                    .with_note(format!("traceback (most recent call last):\n{}", trace))
+            }
+            RuntimeError::UnknownVariable { suggestion, .. }
+            | RuntimeError::UnknownFunction { suggestion, .. } => {
+                with_suggestion(diagnostic.with_label("not defined".to_string()), suggestion)
+            }
+            RuntimeError::UnknownLibrary { suggestion, .. } => with_suggestion(
+                diagnostic.with_label("no library with this name".to_string()),
+                suggestion,
+            ),
+            RuntimeError::DivisionByZero { .. } => {
+                diagnostic.with_label("divided by zero here".to_string())
+            }
+            RuntimeError::NumberTooBig { .. } => diagnostic
+                .with_label("doesn't fit".to_string())
+                .with_help(format!("only numbers between {} and {} work here", i64::MIN, i64::MAX)),
+            RuntimeError::ArgumentMismatch { expected, .. } => {
+                diagnostic.with_label(format!("expected {} arguments", expected))
+            }
+            RuntimeError::TypeError { found, .. } => {
+                diagnostic.with_label(format!("found {}", found))
+            }
+            RuntimeError::ConstantReassign {
+                name, declared_at, ..
+            } => diagnostic
+                .with_label("reassigned here".to_string())
+                .with_note(format!("{} was declared based on {}", name, declared_at)),
+            RuntimeError::GeneralError { .. } | RuntimeError::Other(_) => diagnostic,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(line: usize, column: usize, end_line: usize, end_column: usize) -> Span {
+        Span {
+            line,
+            column,
+            end_line,
+            end_column,
+        }
+    }
+
+    #[test]
+    fn carets_under_the_span() {
+        let diagnostic = Diagnostic::error("unknown variable: scroe".to_string(), Some(span(2, 10, 2, 15)))
+            .with_code("E0009")
+            .with_label("not found".to_string())
+            .with_help("did you mean `score`?".to_string());
+
+        assert_eq!(
+            diagnostic.render("score is 1\ncook yap(scroe)\n", "game.skibidi", false),
+            "\
+error[E0009]: unknown variable: scroe
+ --> game.skibidi:2:10
+  |
+2 | cook yap(scroe)
+  |          ^^^^^ not found
+  = help: did you mean `score`?
+"
+        );
+    }
+
+    #[test]
+    fn tabs_stay_tabs() {
+        let rendered = Diagnostic::warning("x".to_string(), Some(span(1, 3, 1, 4))).render("\t\tx", "t", false);
+
+        assert!(rendered.ends_with("1 | \t\tx\n  | \t\t^\n"), "{}", rendered);
+    }
+
+    #[test]
+    fn spans_over_several_lines_stop_at_the_end_of_the_first() {
+        let diagnostic = Diagnostic::error("x".to_string(), Some(span(1, 6, 3, 5)));
+        let rendered = diagnostic.render("x is \"a\nb\nc\"", "t", false);
+
+        assert!(rendered.ends_with("1 | x is \"a\n  |      ^^\n"), "{}", rendered);
+    }
+
+    #[test]
+    fn no_span_is_just_the_message() {
+        let rendered = Diagnostic::error("couldn't read it".to_string(), None)
+            .with_note("a note".to_string())
+            .render("x is 1", "t", false);
+
+        assert_eq!(rendered, "error: couldn't read it\n = note: a note\n");
+    }
+
+    #[test]
+    fn the_end_of_the_file_has_a_line_too() {
+        let rendered = Diagnostic::error("x".to_string(), Some(span(2, 1, 2, 1))).render("x is\n", "t", false);
+
+        assert!(rendered.ends_with("2 | \n  | ^\n"), "{}", rendered);
+    }
+}
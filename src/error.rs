@@ -1,135 +1,338 @@
 use core::fmt;
 
-use crate::{lexer::Token, parser::Expr};
+use crate::{
+    lexer::{Span, Token},
+    parser::Type,
+};
 
 // pub type Result<T> = std::result::Result<T, ParseError>;
 
 // error.rs
 #[derive(Debug)]
 pub enum ParseError {
-    UnknownVariable {
-        name: String,
-        line: usize,
-    },
     UnexpectedToken {
         expected: Token,
         found: Token,
-        line: usize,
+        span: Span,
     },
     UnknownUnexpectedToken {
         found: Token,
-        line: usize,
+        span: Span,
+    },
+    GeneralError {
+        span: Span,
+        message: String,
+    },
+    // Since token isnt tokenized yet
+    LexerUnexpectedChar {
+        found: String,
+        span: Span,
+    },
+    ArgumentMismatch {
+        expected: usize,
+        found: usize,
+        span: Span,
+    },
+    ConstantReassign {
+        name: String,
+        declared_at: Span,
+        span: Span,
+    },
+    // Found by the typecheck pass, before anything runs
+    TypeMismatch {
+        expected: Type,
+        found: Type,
+        context: String,
+        span: Span,
+    },
+    UnknownType {
+        name: String,
+        suggestion: Option<String>,
+        span: Span,
+    },
+    UnknownVariant {
+        enum_name: String,
+        variant: String,
+        suggestion: Option<String>,
+        span: Span,
+    },
+    Other(String), // Catch-all for other types of errors
+}
+
+// Everything that can go wrong once the script is running
+#[derive(Debug)]
+pub enum RuntimeError {
+    // The suggestion is the closest name that does exist, if there's one near enough
+    UnknownVariable {
+        name: String,
+        suggestion: Option<String>,
+        span: Span,
     },
     UnknownFunction {
         name: String,
-        line: usize,
+        suggestion: Option<String>,
+        span: Span,
+    },
+    UnknownLibrary {
+        name: String,
+        suggestion: Option<String>,
+        span: Span,
     },
     GeneralError {
-        line: usize,
+        span: Span,
         message: String,
     },
     DivisionByZero {
-        line: usize,
+        span: Span,
     },
-    // Since token isnt tokenized yet
-    LexerUnexpectedChar {
-        found: String,
-        line: usize,
+    // Numbers grow as big as they need to, but ranges and libraries only take ones that fit in 64 bits
+    NumberTooBig {
+        value: String,
+        span: Span,
     },
     ArgumentMismatch {
         expected: usize,
         found: usize,
-        line: usize,
+        span: Span,
     },
     TypeError {
-        expected: Expr,
-        found: Expr,
-        line: usize,
+        expected: String, // Type names, not the values, so the error stays small
+        found: String,
+        span: Span,
+    },
+    ConstantReassign {
+        name: String,
+        declared_at: Span,
+        span: Span,
+    },
+    // The error, plus every cookable it went through on the way out. Innermost frame first
+    Traceback {
+        error: Box<RuntimeError>,
+        frames: Vec<TraceFrame>,
     },
     Other(String), // Catch-all for other types of errors
 }
 
-impl fmt::Display for ParseError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+#[derive(Debug, Clone)]
+pub struct TraceFrame {
+    pub function: String,
+    pub class: Option<String>, // Set for methods
+    pub call_site: Span,       // Where it was cooked from, default for the entry function
+}
+
+impl RuntimeError {
+    // Called as the error leaves a cookable
+    pub fn with_frame(self, frame: TraceFrame) -> RuntimeError {
         match self {
-            ParseError::UnknownVariable {
-                name,
-                line,
-            } => {
-                write!(f, "on line {}: unknown variable: {}", line, name)
+            RuntimeError::Traceback { error, mut frames } => {
+                frames.push(frame);
+
+                RuntimeError::Traceback { error, frames }
+            }
+            error => RuntimeError::Traceback {
+                error: Box::new(error),
+                frames: vec![frame],
             },
-            ParseError::UnexpectedToken {
+        }
+    }
+}
+
+// Tokens in messages, quoted the way they're written
+pub fn describe(token: &Token) -> String {
+    match token {
+        Token::EOF => "the end of the file".to_string(),
+        token => format!("`{}`", token),
+    }
+}
+
+// Codes never change meaning once they're out, `skibidyscript explain <code>` has the long
+// version of each one in explain.rs
+impl ParseError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseError::UnexpectedToken { .. } => "E0001",
+            ParseError::UnknownUnexpectedToken { .. } => "E0002",
+            ParseError::LexerUnexpectedChar { .. } => "E0003",
+            ParseError::GeneralError { .. } | ParseError::Other(_) => "E0005",
+            ParseError::ArgumentMismatch { .. } => "E0006",
+            ParseError::ConstantReassign { .. } => "E0007",
+            ParseError::TypeMismatch { .. } => "E0008",
+            ParseError::UnknownType { .. } => "E0016",
+            ParseError::UnknownVariant { .. } => "E0017",
+        }
+    }
+
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            ParseError::UnexpectedToken { span, .. }
+            | ParseError::UnknownUnexpectedToken { span, .. }
+            | ParseError::GeneralError { span, .. }
+            | ParseError::LexerUnexpectedChar { span, .. }
+            | ParseError::ArgumentMismatch { span, .. }
+            | ParseError::ConstantReassign { span, .. }
+            | ParseError::TypeMismatch { span, .. }
+            | ParseError::UnknownType { span, .. }
+            | ParseError::UnknownVariant { span, .. } => Some(*span),
+            ParseError::Other(_) => None,
+        }
+    }
+
+    // The error without where it happened
+    pub fn message(&self) -> String {
+        match self {
+            ParseError::UnexpectedToken { expected, found, .. } => {
+                format!("expected {}, but found {}", describe(expected), describe(found))
+            }
+            ParseError::UnknownUnexpectedToken { found, .. } => {
+                format!("found {}, but it's not expected here", describe(found))
+            }
+            ParseError::GeneralError { message, .. } => message.clone(),
+            ParseError::ArgumentMismatch { expected, found, .. } => {
+                format!("expected {} arguments, but found {}", expected, found)
+            }
+            ParseError::ConstantReassign { name, .. } => {
+                format!("cannot reassign constant {}", name)
+            }
+            ParseError::TypeMismatch {
                 expected,
                 found,
-                line,
+                context,
+                ..
             } => {
-                write!(
-                    f,
-                    "on line {}: Expected token: {:?}, but found token: {:?}",
-                    line, expected, found
-                )
+                format!("{} expected type {}, but found type {}", context, expected, found)
             }
-            ParseError::UnknownUnexpectedToken { found, line } => {
-                write!(
-                    f,
-                    "on line {}: found token: {:?}, but it's not expected",
-                    line, found
-                )
+            ParseError::UnknownType { name, .. } => format!("unknown type: {}", name),
+            ParseError::UnknownVariant { enum_name, variant, .. } => {
+                format!("vibe {} has no variant {}", enum_name, variant)
             }
-            ParseError::GeneralError {
-                line,
-                message,
-            } => {
-                write!(f, "on line {}: {}", line, message)
+            ParseError::LexerUnexpectedChar { found, .. } => {
+                format!("found {:?}, but it's not expected", found)
             }
-            ParseError::UnknownFunction {
-                name,
-                line,
-            } => {
-                write!(
-                    f,
-                    "on line {}: unknown function: {}",
-                    line, name
-                )
+            ParseError::Other(msg) => msg.clone(),
+        }
+    }
+}
+
+impl RuntimeError {
+    // The same mistake gets the same code whether it's caught before or while running
+    pub fn code(&self) -> &'static str {
+        match self {
+            RuntimeError::UnknownVariable { .. } => "E0009",
+            RuntimeError::UnknownFunction { .. } => "E0010",
+            RuntimeError::UnknownLibrary { .. } => "E0011",
+            RuntimeError::DivisionByZero { .. } => "E0012",
+            RuntimeError::NumberTooBig { .. } => "E0018",
+            RuntimeError::TypeError { .. } => "E0014",
+            RuntimeError::GeneralError { .. } | RuntimeError::Other(_) => "E0015",
+            RuntimeError::ArgumentMismatch { .. } => "E0006",
+            RuntimeError::ConstantReassign { .. } => "E0007",
+            RuntimeError::Traceback { error, .. } => error.code(),
+        }
+    }
+
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            RuntimeError::UnknownVariable { span, .. }
+            | RuntimeError::UnknownFunction { span, .. }
+            | RuntimeError::UnknownLibrary { span, .. }
+            | RuntimeError::GeneralError { span, .. }
+            | RuntimeError::DivisionByZero { span }
+            | RuntimeError::NumberTooBig { span, .. }
+            | RuntimeError::ArgumentMismatch { span, .. }
+            | RuntimeError::TypeError { span, .. }
+            | RuntimeError::ConstantReassign { span, .. } => Some(*span),
+            RuntimeError::Traceback { error, .. } => error.span(),
+            RuntimeError::Other(_) => None,
+        }
+    }
+
+    // The error without where it happened or the traceback
+    pub fn message(&self) -> String {
+        match self {
+            RuntimeError::UnknownVariable { name, .. } => format!("unknown variable: {}", name),
+            RuntimeError::UnknownFunction { name, .. } => format!("unknown function: {}", name),
+            RuntimeError::UnknownLibrary { name, .. } => format!("unknown library: {}", name),
+            RuntimeError::GeneralError { message, .. } => message.clone(),
+            RuntimeError::DivisionByZero { .. } => "division by zero".to_string(),
+            // The range is in the diagnostic's help line
+            RuntimeError::NumberTooBig { value, .. } => format!("number {} is too big here", value),
+            RuntimeError::ArgumentMismatch { expected, found, .. } => {
+                format!("expected {} arguments, but found {}", expected, found)
             }
-            ParseError::DivisionByZero {
-                line,
-            } => {
-                write!(
-                    f,
-                    "on line {}: division by zero",
-                    line
-                )
+            RuntimeError::TypeError { expected, found, .. } => {
+                format!("expected type {}, but found type {}", expected, found)
             }
-            ParseError::ArgumentMismatch {
-                expected,
-                found,
-                line,
-            } => {
-                write!(
-                    f,
-                    "on line {}: expected {} arguments, but found {}",
-                    line, expected, found
-                )
-            },
-            ParseError::TypeError {
-                expected,
-                found,
-                line,
-            } => {
-                write!(
-                    f,
-                    "on line {}: expected type {:?}, but found type {:?}",
-                    line, expected, found
-                )
+            RuntimeError::ConstantReassign { name, .. } => {
+                format!("cannot reassign constant {}", name)
+            }
+            RuntimeError::Traceback { error, .. } => error.message(),
+            RuntimeError::Other(msg) => msg.clone(),
+        }
+    }
+}
+
+// The one line version, diagnostic.rs does the fancy one
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::ConstantReassign {
+                declared_at, span, ..
+            } => write!(
+                f,
+                "on {}: {}, it was declared based on {}",
+                span,
+                self.message(),
+                declared_at
+            ),
+            _ => match self.span() {
+                Some(span) => write!(f, "on {}: {}", span, self.message()),
+                None => write!(f, "error: {}", self.message()),
             },
-            ParseError::LexerUnexpectedChar {
-                found,
-                line,
-            } => {
-                write!(f, "on line {}: found token {:?}, but it's not expected", line, found)
+        }
+    }
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // The error, then the frames top-down like python, the entry function first
+            RuntimeError::Traceback { error, frames } => {
+                write!(f, "{}\ntraceback (most recent call last):", error)?;
+
+                for frame in frames.iter().rev() {
+                    write!(f, "\n  {}", frame)?;
+                }
+
+                Ok(())
             }
-            ParseError::Other(msg) => write!(f, "error: {}", msg),
+            RuntimeError::ConstantReassign {
+                declared_at, span, ..
+            } => write!(
+                f,
+                "on {}: {}, it was declared based on {}",
+                span,
+                self.message(),
+                declared_at
+            ),
+            _ => match self.span() {
+                Some(span) => write!(f, "on {}: {}", span, self.message()),
+                None => write!(f, "error: {}", self.message()),
+            },
+        }
+    }
+}
+
+impl fmt::Display for TraceFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.class {
+            Some(class) => write!(f, "in {}.{}", class, self.function)?,
+            None => write!(f, "in {}", self.function)?,
         }
+
+        if self.call_site != Span::default() {
+            write!(f, ", cooked on {}", self.call_site)?;
+        }
+
+        Ok(())
     }
 }
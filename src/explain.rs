@@ -0,0 +1,682 @@
+// explain.rs
+
+// The long version of every error and warning code, for `skibidyscript explain E0001`.
+// Codes are never reused, if an error goes away its code stays retired.
+
+pub fn explanation(code: &str) -> Option<&'static str> {
+    let text = match code.to_uppercase().as_str() {
+        "E0001" => {
+            "\
+E0001: expected something else here
+
+The parser knew exactly what had to come next, a bracket, `eat`, `slay` or
+the like, and found something different. Often a missing comma or bracket.
+
+Bad:
+
+    cookable fein()
+        cook yap(1 2)
+    slay
+
+Fixed:
+
+    cookable fein()
+        cook yap(1, 2)
+    slay"
+        }
+        "E0002" => {
+            "\
+E0002: not expected here
+
+The token is fine on its own, but nothing can start or carry on with it at
+this spot, like arguments without the brackets around them.
+
+Bad:
+
+    cookable fein()
+        cook yap \"hi\"
+    slay
+
+Fixed:
+
+    cookable fein()
+        cook yap(\"hi\")
+    slay"
+        }
+        "E0003" => {
+            "\
+E0003: unexpected character
+
+The character isn't part of skibidiscript at all, so it can't be turned into
+a token.
+
+Bad:
+
+    cookable fein()
+        cook yap(10 % 3)
+    slay
+
+Fixed:
+
+    cookable fein()
+        cook yap(10 - 10 / 3 * 3)
+    slay"
+        }
+        "E0004" => {
+            "\
+E0004: number is too big
+
+Not reported anymore. Numbers grow as big as they need to, so a literal of
+any length is fine."
+        }
+        "E0005" => {
+            "\
+E0005: syntax error
+
+Something is written in a way the parser can't make sense of and that has no
+more specific code, like a type annotation that isn't a type.
+
+Bad:
+
+    cookable fein()
+        score: 5 is 1
+        cook yap(score)
+    slay
+
+Fixed:
+
+    cookable fein()
+        score: number is 1
+        cook yap(score)
+    slay"
+        }
+        "E0006" => {
+            "\
+E0006: wrong number of arguments
+
+A cookable was cooked with more or fewer arguments than it has parameters.
+This is caught before the script runs when the cookable can be found, and
+while it runs otherwise.
+
+Bad:
+
+    cookable add(a, b)
+        blud a + b
+    slay
+
+    cookable fein()
+        cook yap(cook add(1))
+    slay
+
+Fixed:
+
+    cookable add(a, b)
+        blud a + b
+    slay
+
+    cookable fein()
+        cook yap(cook add(1, 2))
+    slay"
+        }
+        "E0007" => {
+            "\
+E0007: cannot reassign a constant
+
+A variable declared with `based` can't be given a new value.
+
+Bad:
+
+    based LIVES is 3
+
+    cookable fein()
+        LIVES is LIVES - 1
+    slay
+
+Fixed:
+
+    lives is 3
+
+    cookable fein()
+        lives is lives - 1
+    slay"
+        }
+        "E0008" => {
+            "\
+E0008: mismatched types
+
+The type checker found a value whose type can't be right, before the script
+ran. Annotations are optional, anything without one is `any` and always
+passes.
+
+Bad:
+
+    cookable fein()
+        score: number is \"ten\"
+        cook yap(score)
+    slay
+
+Fixed:
+
+    cookable fein()
+        score: number is 10
+        cook yap(score)
+    slay"
+        }
+        "E0009" => {
+            "\
+E0009: unknown variable
+
+The name was read before anything was assigned to it. Often a typo, the
+error suggests a close match when there is one.
+
+Bad:
+
+    cookable fein()
+        score is 10
+        cook yap(scroe)
+    slay
+
+Fixed:
+
+    cookable fein()
+        score is 10
+        cook yap(score)
+    slay"
+        }
+        "E0010" => {
+            "\
+E0010: unknown function
+
+There's no cookable with this name, on its own, on the library or on the
+pookie it was cooked on.
+
+Bad:
+
+    cookable greet()
+        cook yap(\"hi\")
+    slay
+
+    cookable fein()
+        cook greeet()
+    slay
+
+Fixed:
+
+    cookable greet()
+        cook yap(\"hi\")
+    slay
+
+    cookable fein()
+        cook greet()
+    slay"
+        }
+        "E0011" => {
+            "\
+E0011: unknown library
+
+`gyatt` only knows the libraries that come with skibidiscript: nerd, skui and
+apel.
+
+Bad:
+
+    gyatt nerds
+
+Fixed:
+
+    gyatt nerd"
+        }
+        "E0012" => {
+            "\
+E0012: division by zero
+
+A number was divided by 0 while the script was running.
+
+Bad:
+
+    cookable average(total, count)
+        blud total / count
+    slay
+
+    cookable fein()
+        cook yap(cook average(10, 0))
+    slay
+
+Fixed:
+
+    cookable average(total, count)
+        sus(count rizz 0) eat
+            blud 0
+        slay
+
+        blud total / count
+    slay
+
+    cookable fein()
+        cook yap(cook average(10, 0))
+    slay"
+        }
+        "E0013" => {
+            "\
+E0013: overflow
+
+Not reported anymore. A sum that goes past -9223372036854775808 or
+9223372036854775807 carries on as a big number instead of stopping the
+script."
+        }
+        "E0014" => {
+            "\
+E0014: wrong type while running
+
+A value had the wrong type once the script was running. The type checker
+lets values it doesn't know the type of through, annotating the parameter
+moves the error to before the script runs.
+
+Bad:
+
+    cookable half(x)
+        blud x / 2
+    slay
+
+    cookable fein()
+        cook yap(cook half(\"ten\"))
+    slay
+
+Fixed:
+
+    cookable half(x: number)
+        blud x / 2
+    slay
+
+    cookable fein()
+        cook yap(cook half(10))
+    slay"
+        }
+        "E0015" => {
+            "\
+E0015: runtime error
+
+Something went wrong while the script was running that has no more specific
+code, like using `goat` where there's no pookie.
+
+Bad:
+
+    cookable fein()
+        goat.score is 1
+    slay
+
+Fixed:
+
+    pookie Game()
+        cookable __edge__()
+            goat.score is 1
+        slay
+    slay
+
+    cookable fein()
+        game is mew Game()
+        cook yap(game.score)
+    slay"
+        }
+        "E0016" => {
+            "\
+E0016: unknown type
+
+An annotation names a type that doesn't exist. The types are number, string,
+bool, list, range, generator and any, plus the pookies and vibes declared in
+the script. Often a typo, the error suggests a close match when there is one.
+
+Bad:
+
+    cookable double(x: nubmer) -> number
+        blud x * 2
+    slay
+
+Fixed:
+
+    cookable double(x: number) -> number
+        blud x * 2
+    slay"
+        }
+        "E0017" => {
+            "\
+E0017: unknown variant
+
+A vibe is used with a variant it doesn't declare. In a vibecheck arm that
+would mean the arm could never match, so it's an error before the script
+runs.
+
+Bad:
+
+    vibe GameState
+        Playing
+        GameOver(score)
+    slay
+
+    cookable describe(state: GameState) -> string
+        vibecheck (state) eat
+            GameState.GameOvr(score) eat
+                blud \"game over\"
+            slay
+            _ eat
+                blud \"playing\"
+            slay
+        slay
+    slay
+
+Fixed:
+
+    vibe GameState
+        Playing
+        GameOver(score)
+    slay
+
+    cookable describe(state: GameState) -> string
+        vibecheck (state) eat
+            GameState.GameOver(score) eat
+                blud \"game over\"
+            slay
+            _ eat
+                blud \"playing\"
+            slay
+        slay
+    slay"
+        }
+        "E0018" => {
+            "\
+E0018: number too big here
+
+Numbers can be as big as they like, but a few things only take numbers
+between -9223372036854775808 and 9223372036854775807: the ends and step of a
+range, and numbers given to libraries like nerd.randInt or skui.
+
+Bad:
+
+    cookable fein()
+        goon (i in 0..99999999999999999999) eat
+            cook yap(i)
+        slay
+    slay
+
+Fixed:
+
+    cookable fein()
+        goon (i in 0..9223372036854775807) eat
+            cook yap(i)
+        slay
+    slay"
+        }
+        "W0001" => {
+            "\
+W0001: undefined_function
+
+A cookable is cooked that isn't declared anywhere in the script, or goat is
+asked for a method its pookie doesn't have. The script would stop with E0010
+when it gets there.
+
+Bad:
+
+    cookable fein()
+        cook yapp(\"hi\")
+    slay
+
+Fixed:
+
+    cookable fein()
+        cook yap(\"hi\")
+    slay
+
+Turn it off with --allow undefined_function."
+        }
+        "W0002" => {
+            "\
+W0002: unknown_library_member
+
+The library is imported, but it has no cookable with this name.
+
+Bad:
+
+    gyatt nerd
+
+    cookable fein()
+        cook yap(cook nerd.randint(1, 6))
+    slay
+
+Fixed:
+
+    gyatt nerd
+
+    cookable fein()
+        cook yap(cook nerd.randInt(1, 6))
+    slay
+
+Turn it off with --allow unknown_library_member."
+        }
+        "W0003" => {
+            "\
+W0003: goat_outside_pookie
+
+`goat` is the pookie a method was cooked on, so it only exists inside the
+cookables of a pookie.
+
+Bad:
+
+    cookable reset()
+        goat.score is 0
+    slay
+
+Fixed:
+
+    pookie Game()
+        cookable __edge__()
+            goat.score is 0
+        slay
+
+        cookable reset()
+            goat.score is 0
+        slay
+    slay
+
+Turn it off with --allow goat_outside_pookie."
+        }
+        "W0004" => {
+            "\
+W0004: unreachable_code
+
+`blud` leaves the cookable straight away, so nothing after it in the same
+block ever runs.
+
+Bad:
+
+    cookable double(x)
+        blud x * 2
+        cook yap(\"doubled\")
+    slay
+
+    cookable fein()
+        cook yap(cook double(2))
+    slay
+
+Fixed:
+
+    cookable double(x)
+        cook yap(\"doubled\")
+        blud x * 2
+    slay
+
+    cookable fein()
+        cook yap(cook double(2))
+    slay
+
+Turn it off with --allow unreachable_code."
+        }
+        "W0005" => {
+            "\
+W0005: unused_variable
+
+A parameter, goon variable or vibecheck binding is never read. Start the
+name with _ if that's on purpose.
+
+Bad:
+
+    cookable fein()
+        goon (i in 0..3) eat
+            cook yap(\"hi\")
+        slay
+    slay
+
+Fixed:
+
+    cookable fein()
+        goon (_i in 0..3) eat
+            cook yap(\"hi\")
+        slay
+    slay
+
+Turn it off with --allow unused_variable."
+        }
+        "W0006" => {
+            "\
+W0006: unused_assignment
+
+A variable gets a value, but nothing ever reads it. Start the name with _ if
+that's on purpose.
+
+Bad:
+
+    cookable fein()
+        total is 10
+        cook yap(10)
+    slay
+
+Fixed:
+
+    cookable fein()
+        total is 10
+        cook yap(total)
+    slay
+
+Turn it off with --allow unused_assignment."
+        }
+        "W0007" => {
+            "\
+W0007: unused_function
+
+Nothing cooks this cookable. The entry function (fein, or whatever --entry
+names) doesn't count.
+
+Bad:
+
+    cookable helper()
+        blud 1
+    slay
+
+    cookable fein()
+        cook yap(1)
+    slay
+
+Fixed:
+
+    cookable helper()
+        blud 1
+    slay
+
+    cookable fein()
+        cook yap(cook helper())
+    slay
+
+Turn it off with --allow unused_function."
+        }
+        "W0008" => {
+            "\
+W0008: missing_edge
+
+`mew` runs `__edge__` to set up a new pookie, so a pookie without one can't
+be made. The script only stops with E0010 once it tries.
+
+Bad:
+
+    pookie Zombie()
+        cookable run()
+            cook yap(\"running\")
+        slay
+    slay
+
+Fixed:
+
+    pookie Zombie()
+        cookable __edge__()
+        slay
+
+        cookable run()
+            cook yap(\"running\")
+        slay
+    slay
+
+Turn it off with --allow missing_edge."
+        }
+        "W0009" => {
+            "\
+W0009: ghost_outside_loop
+
+`ghost` skips to the next round of the loop it's in. Outside of a goon or
+skibidi loop it does nothing.
+
+Bad:
+
+    cookable fein()
+        cook yap(\"hi\")
+        ghost
+    slay
+
+Fixed:
+
+    cookable fein()
+        goon (x in [1, 2, 3]) eat
+            sus(x rizz 2) eat
+                ghost
+            slay
+
+            cook yap(x)
+        slay
+    slay
+
+Turn it off with --allow ghost_outside_loop."
+        }
+        _ => return None,
+    };
+
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lint::LintKind;
+
+    #[test]
+    fn every_code_is_explained() {
+        let errors = (1..=18).map(|n| format!("E{:04}", n));
+        let lints = LintKind::ALL.iter().map(|kind| kind.code().to_string());
+
+        for code in errors.chain(lints) {
+            let text = explanation(&code).unwrap_or_else(|| panic!("{} has no explanation", code));
+
+            assert!(text.starts_with(&format!("{}: ", code)), "{}", code);
+        }
+    }
+
+    #[test]
+    fn lints_say_how_to_turn_them_off() {
+        for kind in LintKind::ALL {
+            let text = explanation(kind.code()).unwrap();
+
+            assert!(text.ends_with(&format!("--allow {}.", kind.name())), "{}", kind.name());
+        }
+    }
+
+    #[test]
+    fn codes_ignore_case() {
+        assert_eq!(explanation("e0001"), explanation("E0001"));
+        assert_eq!(explanation("E9999"), None);
+    }
+}
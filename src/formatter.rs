@@ -0,0 +1,559 @@
+// formatter.rs
+
+// `skibidyscript fmt`, prints a script back out from its syntax tree in the one layout everything
+// should have: four spaces per block, one statement per line, spaces around operators, at most
+// one blank line in a row and always one around cookables, pookies and vibes.
+//
+// The tree doesn't know about comments, so the lexer keeps them on the side and they're slotted
+// back in by line number. A comment on a line of its own stays on a line of its own, one after
+// some code stays after that code.
+
+use crate::{
+    lexer::Comment,
+    parser::{EnumVariant, Expr, MatchArm, Param, Pattern, Stmt, Type},
+};
+
+const INDENT: &str = "    ";
+
+pub fn format(source: &str, statements: &[Stmt], comments: &[Comment]) -> String {
+    let mut formatter = Formatter {
+        lines: source.lines().collect(),
+        comments,
+        next_comment: 0,
+        out: String::new(),
+        indent: 0,
+        last_line: 0,
+        opened: true,
+    };
+
+    formatter.block(statements, 0, true);
+
+    // Whatever is left is after the last statement
+    formatter.leading(usize::MAX);
+
+    if formatter.out.is_empty() {
+        return formatter.out;
+    }
+
+    formatter.out.trim_end().to_string() + "\n"
+}
+
+struct Formatter<'a> {
+    lines: Vec<&'a str>,
+    comments: &'a [Comment],
+    next_comment: usize,
+    out: String,
+    indent: usize,
+    // The source line of whatever was written last, to tell where the blank lines were
+    last_line: usize,
+    // Nothing written since a block was opened, which never starts with a blank line
+    opened: bool,
+}
+
+impl Formatter<'_> {
+    fn line(&mut self, text: &str) {
+        for _ in 0..self.indent {
+            self.out += INDENT;
+        }
+
+        self.out += text;
+        self.out += "\n";
+        self.opened = false;
+    }
+
+    fn blank_line(&mut self) {
+        if !self.opened && !self.out.ends_with("\n\n") {
+            self.out += "\n";
+        }
+    }
+
+    // A blank line if there was one in the source before this line
+    fn gap(&mut self, line: usize) {
+        if self.last_line > 0 && line > self.last_line + 1 {
+            self.blank_line();
+        }
+    }
+
+    // Comments on their own lines before this one
+    fn leading(&mut self, line: usize) {
+        while let Some(comment) = self.comments.get(self.next_comment) {
+            if comment.span.line >= line {
+                break;
+            }
+
+            self.gap(comment.span.line);
+            self.line(&format!("--{}", comment.text));
+            self.last_line = comment.span.line;
+            self.next_comment += 1;
+        }
+    }
+
+    // A comment after the code on this line goes after the code that was just written
+    fn trailing(&mut self, line: usize) {
+        while let Some(comment) = self.comments.get(self.next_comment) {
+            if comment.own_line || comment.span.line > line {
+                break;
+            }
+
+            self.out.pop();
+            self.out += &format!("  --{}\n", comment.text);
+            self.next_comment += 1;
+        }
+    }
+
+    // The first line after this one with code on it, for keywords the tree doesn't keep a span
+    // for, like cap and the slay of a vibecheck arm
+    fn next_code_line(&self, after: usize) -> usize {
+        (after + 1..=self.lines.len())
+            .find(|line| {
+                let text = self.lines[line - 1].trim();
+                !text.is_empty() && !text.starts_with("--")
+            })
+            .unwrap_or(after + 1)
+    }
+
+    fn block(&mut self, statements: &[Stmt], end_line: usize, declarations: bool) {
+        let mut previous: Option<&Stmt> = None;
+
+        for stmt in statements {
+            let span = stmt.span();
+
+            // Cookables, pookies and vibes get room to breathe
+            if declarations
+                && previous.is_some_and(|previous| is_declaration(previous) || is_declaration(stmt))
+            {
+                self.blank_line();
+            }
+
+            self.leading(span.line);
+            self.gap(span.line);
+            self.statement(stmt);
+            self.last_line = self.last_line.max(span.end_line);
+
+            previous = Some(stmt);
+        }
+
+        self.leading(end_line);
+    }
+
+    // The body of a block and the slay that closes it
+    fn body(&mut self, statements: &[Stmt], header_line: usize, slay_line: usize, declarations: bool) {
+        self.last_line = header_line;
+        self.indent += 1;
+        self.block(statements, slay_line, declarations);
+        self.indent -= 1;
+
+        self.line("slay");
+        self.trailing(slay_line);
+        self.last_line = slay_line;
+    }
+
+    fn header(&mut self, text: &str, line: usize) {
+        self.line(text);
+        self.trailing(line);
+        self.opened = true;
+    }
+
+    fn statement(&mut self, stmt: &Stmt) {
+        let span = stmt.span();
+
+        match stmt {
+            Stmt::Class { name, functions, .. } => {
+                self.header(&format!("pookie {}()", name), span.line);
+                self.body(functions, span.line, span.end_line, true);
+            }
+            Stmt::Function {
+                name,
+                params,
+                return_type,
+                body,
+                ..
+            } => {
+                self.header(&function_header(name, params, return_type), span.line);
+                self.body(body, span.line, span.end_line, false);
+            }
+            Stmt::While { condition, body, .. } => {
+                self.header(&format!("skibidi ({}) eat", expression(condition)), span.line);
+                self.body(body, span.line, span.end_line, false);
+            }
+            Stmt::ForLoop {
+                iterators,
+                collection,
+                body,
+                ..
+            } => {
+                self.header(
+                    &format!("goon ({} in {}) eat", iterators.join(", "), expression(collection)),
+                    span.line,
+                );
+                self.body(body, span.line, span.end_line, false);
+            }
+            Stmt::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                self.header(&format!("sus ({}) eat", expression(condition)), span.line);
+
+                match else_branch {
+                    Some(else_branch) => {
+                        let then_end = then_branch.last().map_or(span.line, |stmt| stmt.span().end_line);
+                        let cap_line = self.next_code_line(then_end);
+
+                        self.last_line = span.line;
+                        self.indent += 1;
+                        self.block(then_branch, cap_line, false);
+                        self.indent -= 1;
+
+                        self.header("cap", cap_line);
+                        self.body(else_branch, cap_line, span.end_line, false);
+                    }
+                    None => self.body(then_branch, span.line, span.end_line, false),
+                }
+            }
+            Stmt::Enum { name, variants, .. } => {
+                self.header(&format!("vibe {}", name), span.line);
+                self.variants(variants, span.line, span.end_line);
+            }
+            Stmt::Match { subject, arms, .. } => {
+                self.header(&format!("vibecheck ({}) eat", expression(subject)), span.line);
+                self.arms(arms, span.line, span.end_line);
+            }
+            _ => {
+                self.line(&simple_statement(stmt));
+                self.trailing(span.end_line);
+            }
+        }
+    }
+
+    fn variants(&mut self, variants: &[EnumVariant], header_line: usize, slay_line: usize) {
+        self.last_line = header_line;
+        self.indent += 1;
+
+        for variant in variants {
+            let line = variant.span.line;
+
+            self.leading(line);
+            self.gap(line);
+
+            if variant.fields.is_empty() {
+                self.header(&variant.name, line);
+            } else {
+                self.header(&format!("{}({})", variant.name, variant.fields.join(", ")), line);
+            }
+
+            self.last_line = line;
+        }
+
+        self.leading(slay_line);
+        self.indent -= 1;
+
+        self.line("slay");
+        self.trailing(slay_line);
+        self.last_line = slay_line;
+    }
+
+    fn arms(&mut self, arms: &[MatchArm], header_line: usize, slay_line: usize) {
+        self.last_line = header_line;
+        self.indent += 1;
+
+        for arm in arms {
+            let line = self.next_code_line(self.last_line);
+
+            self.leading(line);
+            self.gap(line);
+            self.header(&format!("{} eat", pattern(&arm.pattern)), line);
+
+            let body_end = arm.body.last().map_or(line, |stmt| stmt.span().end_line);
+            let arm_slay_line = self.next_code_line(body_end);
+
+            self.body(&arm.body, line, arm_slay_line, false);
+        }
+
+        self.leading(slay_line);
+        self.indent -= 1;
+
+        self.line("slay");
+        self.trailing(slay_line);
+        self.last_line = slay_line;
+    }
+}
+
+fn is_declaration(stmt: &Stmt) -> bool {
+    matches!(stmt, Stmt::Function { .. } | Stmt::Class { .. } | Stmt::Enum { .. })
+}
+
+// Statements that fit on one line
+fn simple_statement(stmt: &Stmt) -> String {
+    match stmt {
+        Stmt::VariableAssign {
+            name,
+            object,
+            type_annotation,
+            value,
+            ..
+        } => {
+            let target = match object {
+                Some(object) => format!("{}.{}", operand(object), name),
+                None => name.clone(),
+            };
+
+            format!("{}{} is {}", target, annotation(type_annotation), expression(value))
+        }
+        Stmt::Constant {
+            name,
+            type_annotation,
+            value,
+            ..
+        } => format!("based {}{} is {}", name, annotation(type_annotation), expression(value)),
+        Stmt::DestructureAssign { names, value, .. } => {
+            format!("{} is {}", names.join(", "), expression(value))
+        }
+        // A cooked cookable on its own always says cook, it isn't a statement without it
+        Stmt::Expression { value, .. } => match value.unspanned() {
+            Expr::FunctionCall { .. } => format!("cook {}", operand(value)),
+            _ => operand(value),
+        },
+        Stmt::Return { value, .. } => match value {
+            // blud x, y makes a list without a span, blud [x, y] has one
+            Expr::List(values) => format!("blud {}", list(values)),
+            _ => format!("blud {}", expression(value)),
+        },
+        Stmt::Yield { value, .. } => format!("yeet {}", expression(value)),
+        Stmt::Import { library, .. } => format!("gyatt {}", library),
+        Stmt::Continue { .. } => "ghost".to_string(),
+        _ => unreachable!("blocks are formatted by Formatter::statement"),
+    }
+}
+
+fn annotation(type_annotation: &Option<Type>) -> String {
+    match type_annotation {
+        Some(type_annotation) => format!(": {}", type_annotation),
+        None => String::new(),
+    }
+}
+
+// cookable add(a: number, b: number) -> number, the language server shows it too
+pub fn function_header(name: &str, params: &[Param], return_type: &Option<Type>) -> String {
+    let params: Vec<String> = params.iter().map(Param::to_string).collect();
+
+    match return_type {
+        Some(return_type) => format!("cookable {}({}) -> {}", name, params.join(", "), return_type),
+        None => format!("cookable {}({})", name, params.join(", ")),
+    }
+}
+
+fn list(values: &[Expr]) -> String {
+    values.iter().map(expression).collect::<Vec<String>>().join(", ")
+}
+
+fn pattern(pattern: &Pattern) -> String {
+    match pattern {
+        Pattern::Variant {
+            enum_name,
+            variant,
+            bindings,
+        } => format!("{}.{}({})", enum_name, variant, bindings.join(", ")),
+        Pattern::Value(value) => operand(value),
+        Pattern::Wildcard => "_".to_string(),
+    }
+}
+
+// An expression that has a slot to itself, an argument, a list element, the value of an
+// assignment. `cook` takes everything up to the end of the slot with it, so only here can a
+// call have one without changing what the script means
+fn expression(expr: &Expr) -> String {
+    match expr.unspanned() {
+        Expr::FunctionCall { object, .. } if !is_variant(object) => format!("cook {}", operand(expr)),
+        _ => operand(expr),
+    }
+}
+
+// GameState.GameOver(12) makes a vibe, it doesn't cook anything
+fn is_variant(object: &Option<Box<Expr>>) -> bool {
+    match object.as_deref().map(Expr::unspanned) {
+        Some(Expr::Ident(name)) => name.starts_with(char::is_uppercase),
+        _ => false,
+    }
+}
+
+// An expression as part of a bigger one. There are no brackets for grouping, so the tree is
+// always shaped the way the precedence says and writing it back in order parses the same
+fn operand(expr: &Expr) -> String {
+    match expr.unspanned() {
+        Expr::Ident(name) => name.clone(),
+        Expr::Number(value) => value.to_string(),
+        Expr::BigNumber(value) => value.to_string(),
+        Expr::StringLiteral(value) => format!("\"{}\"", value),
+        Expr::Boolean(true) => "sigma".to_string(),
+        Expr::Boolean(false) => "ohio".to_string(),
+        Expr::List(values) => format!("[{}]", list(values)),
+        Expr::FunctionCall { name, object, args } => match object {
+            Some(object) => format!("{}.{}({})", operand(object), name, list(args)),
+            None => format!("{}({})", name, list(args)),
+        },
+        Expr::BinOp { left, op, right } => format!("{} {} {}", operand(left), op, operand(right)),
+        Expr::ObjectValue { object, name } => format!("{}.{}", operand(object), name),
+        Expr::NewInstance { class_name, args } => format!("mew {}({})", class_name, list(args)),
+        Expr::Not(value) => format!("not {}", operand(value)),
+        Expr::Range {
+            start,
+            end,
+            step,
+            inclusive,
+        } => {
+            let dots = if *inclusive { "..=" } else { ".." };
+            let step = match step {
+                Some(step) => format!(" by {}", operand(step)),
+                None => String::new(),
+            };
+
+            format!("{}{}{}{}", operand(start), dots, operand(end), step)
+        }
+        Expr::EnumVariant {
+            enum_name,
+            variant,
+            payload,
+        } => format!("{}.{}({})", enum_name, variant, list(payload)),
+        // Only made while a script runs, never by the parser
+        other => format!("{:?}", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::Lexer, parser::Parser};
+
+    fn fmt(source: &str) -> String {
+        let mut lexer = Lexer::new(source);
+        let (statements, errors) = Parser::new(&mut lexer).unwrap().parse();
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        format(source, &statements, &lexer.comments)
+    }
+
+    #[test]
+    fn lays_a_script_out() {
+        let source = "\
+gyatt nerd
+-- the score
+based MAX is 3
+cookable fein( args )
+      x is 1+2*3   -- math
+
+
+      sus(x rizz 7) eat
+   cook yap( \"seven\" ,x)
+      cap
+ cook yap([1,2 ,3])
+      slay
+slay
+";
+
+        assert_eq!(
+            fmt(source),
+            "\
+gyatt nerd
+-- the score
+based MAX is 3
+
+cookable fein(args)
+    x is 1 + 2 * 3  -- math
+
+    sus (x rizz 7) eat
+        cook yap(\"seven\", x)
+    cap
+        cook yap([1, 2, 3])
+    slay
+slay
+"
+        );
+    }
+
+    #[test]
+    fn keywords_keep_a_space_before_their_brackets() {
+        let source = "\
+x is 0
+skibidi(x < 3) eat
+x is x+1
+slay
+goon(i in 0..3) eat
+  sus(i rizz 1)eat
+      ghost
+  slay
+slay
+vibecheck(x) eat
+3 eat
+slay
+slay
+";
+
+        assert_eq!(
+            fmt(source),
+            "\
+x is 0
+skibidi (x < 3) eat
+    x is x + 1
+slay
+goon (i in 0..3) eat
+    sus (i rizz 1) eat
+        ghost
+    slay
+slay
+vibecheck (x) eat
+    3 eat
+    slay
+slay
+"
+        );
+    }
+
+    #[test]
+    fn newer_syntax() {
+        let source = "\
+vibe S
+    Menu
+    Over(score)
+slay
+
+cookable counts(n: number) -> generator
+    goon (i in 0..=n by 2) eat
+        yeet i
+    slay
+slay
+
+vibecheck (S.Over(1)) eat
+    S.Over(score) eat
+        a, b is [score, 2]
+    slay
+    _ eat
+    slay
+slay
+";
+
+        assert_eq!(fmt(source), source);
+    }
+
+    #[test]
+    fn comments_at_the_end_stay() {
+        assert_eq!(fmt("x is 1\n\n\n-- the end\n"), "x is 1\n\n-- the end\n");
+        assert_eq!(fmt("-- only a comment"), "-- only a comment\n");
+        assert_eq!(fmt(""), "");
+    }
+
+    // Formatting what was already formatted changes nothing
+    #[test]
+    fn examples_format_the_same_twice() {
+        let examples = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+
+        for entry in std::fs::read_dir(examples).unwrap() {
+            let path = entry.unwrap().path();
+            let source = std::fs::read_to_string(&path).unwrap();
+
+            let once = fmt(&source);
+            assert_eq!(fmt(&once), once, "{}", path.display());
+        }
+    }
+}
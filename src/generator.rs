@@ -0,0 +1,347 @@
+// generator.rs
+
+// Generators are cookables with a `yeet` in them. Calling one doesn't run anything, it hands
+// back a generator value that runs until the next yeet every time it's resumed.
+//
+// execute_statement is a plain recursive tree walk, so it can't stop halfway through a loop
+// and pick up again later. Generator bodies are run here instead, with the blocks and loops
+// kept on an explicit stack of frames that survives between resumes.
+
+use std::collections::HashMap;
+
+use crate::{
+    error,
+    interpreter::{Interpreter, UserFunction},
+    parser::{Expr, Stmt},
+    range::RangeIter,
+};
+
+pub struct Generator {
+    function: String, // The cookable it came from, for the debugger's call stack and tracebacks
+    class: Option<String>,
+    frames: Vec<Frame>,
+    locals: HashMap<String, Expr>, // Parameters and everything the body assigns
+    instance: Option<String>, // goat, when a method is the generator
+    done: bool,
+}
+
+// Where a goon loop is getting its values from
+pub enum LoopItems {
+    List(std::vec::IntoIter<Expr>),
+    Range(RangeIter),
+    Generator(usize),
+}
+
+enum Frame {
+    Block {
+        body: Vec<Stmt>,
+        pc: usize,
+    },
+    // A pc past the end of the body means the condition has to be checked again
+    While {
+        condition: Expr,
+        body: Vec<Stmt>,
+        pc: usize,
+    },
+    ForLoop {
+        iterators: Vec<String>,
+        items: LoopItems,
+        body: Vec<Stmt>,
+        pc: usize,
+    },
+}
+
+// Does the function body yeet anywhere, making it a generator
+pub fn body_yields(body: &[Stmt]) -> bool {
+    body.iter().any(|stmt| match stmt {
+        Stmt::Yield { .. } => true,
+        Stmt::While { body, .. } | Stmt::ForLoop { body, .. } => body_yields(body),
+        Stmt::If {
+            then_branch,
+            else_branch,
+            ..
+        } => body_yields(then_branch) || else_branch.as_deref().is_some_and(body_yields),
+        Stmt::Match { arms, .. } => arms.iter().any(|arm| body_yields(&arm.body)),
+        _ => false,
+    })
+}
+
+impl Interpreter {
+    pub fn create_generator(&mut self, function: &UserFunction, locals: HashMap<String, Expr>) -> Expr {
+        let id = self.next_generator_id;
+        self.next_generator_id += 1;
+
+        self.generators.insert(
+            id,
+            Generator {
+                function: function.name.clone(),
+                class: function.class.clone(),
+                frames: vec![Frame::Block {
+                    body: function.body.clone(),
+                    pc: 0,
+                }],
+                locals,
+                instance: self.current_instance.clone(),
+                done: false,
+            },
+        );
+
+        Expr::Generator {
+            function: function.name.clone(),
+            id,
+        }
+    }
+
+    // Runs the generator until its next yeet, None once it's cooked
+    pub fn resume_generator(&mut self, id: usize) -> Result<Option<Expr>, error::RuntimeError> {
+        // Taken out while it runs, so a generator resuming itself is caught
+        let mut generator = self.generators.remove(&id).ok_or_else(|| {
+            error::RuntimeError::GeneralError {
+                span: self.span,
+                message: "Generator is already running".to_string(),
+            }
+        })?;
+
+        if generator.done {
+            self.generators.insert(id, generator);
+            return Ok(None);
+        }
+
+        self.locals.push(std::mem::take(&mut generator.locals));
+        let previous_frame = self.generator_frame.replace(self.locals.len() - 1);
+        // Where it was resumed from, it has no call site of its own once it's running
+        let trace_frame = error::TraceFrame {
+            function: generator.function.clone(),
+            class: generator.class.clone(),
+            call_site: self.span,
+        };
+        self.calls.push(trace_frame.clone());
+        let previous_instance = std::mem::replace(&mut self.current_instance, generator.instance.clone());
+
+        let result = self.run_frames(&mut generator.frames);
+
+        self.generator_frame = previous_frame;
+        self.current_instance = previous_instance;
+        self.calls.pop();
+        generator.locals = self.locals.pop().unwrap_or_default();
+
+        if !matches!(result, Ok(Some(_))) {
+            generator.done = true;
+            generator.frames.clear();
+        }
+
+        self.generators.insert(id, generator);
+
+        result.map_err(|error| error.with_frame(trace_frame))
+    }
+
+    pub fn loop_items(&mut self, collection: Expr) -> Result<LoopItems, error::RuntimeError> {
+        match collection {
+            Expr::List(items) => Ok(LoopItems::List(items.into_iter())),
+            range @ Expr::Range { .. } => Ok(LoopItems::Range(self.range_iter(&range)?)),
+            Expr::Generator { id, .. } => Ok(LoopItems::Generator(id)),
+            other => Err(error::RuntimeError::TypeError {
+                expected: "list".to_string(),
+                found: other.type_name(),
+                span: self.span,
+            }),
+        }
+    }
+
+    pub fn next_item(&mut self, items: &mut LoopItems) -> Result<Option<Expr>, error::RuntimeError> {
+        match items {
+            LoopItems::List(items) => Ok(items.next()),
+            LoopItems::Range(range) => Ok(range.next().map(Expr::Number)),
+            LoopItems::Generator(id) => self.resume_generator(*id),
+        }
+    }
+
+    fn run_frames(&mut self, frames: &mut Vec<Frame>) -> Result<Option<Expr>, error::RuntimeError> {
+        loop {
+            // Find the next statement to run, finishing loops and blocks on the way
+            let stmt = match frames.last_mut() {
+                None => return Ok(None),
+                Some(Frame::Block { body, pc }) => {
+                    if *pc >= body.len() {
+                        frames.pop();
+                        continue;
+                    }
+
+                    *pc += 1;
+                    body[*pc - 1].clone()
+                }
+                Some(Frame::While {
+                    condition,
+                    body,
+                    pc,
+                }) => {
+                    if *pc >= body.len() {
+                        if !self.condition_holds(condition.clone())? {
+                            frames.pop();
+                            continue;
+                        }
+
+                        *pc = 0;
+                    }
+
+                    if body.is_empty() {
+                        continue;
+                    }
+
+                    *pc += 1;
+                    body[*pc - 1].clone()
+                }
+                Some(Frame::ForLoop {
+                    iterators,
+                    items,
+                    body,
+                    pc,
+                }) => {
+                    if *pc >= body.len() {
+                        match self.next_item(items)? {
+                            Some(item) => self.bind_iterators(iterators, item)?,
+                            None => {
+                                frames.pop();
+                                continue;
+                            }
+                        }
+
+                        *pc = 0;
+                    }
+
+                    if body.is_empty() {
+                        continue;
+                    }
+
+                    *pc += 1;
+                    body[*pc - 1].clone()
+                }
+            };
+
+            // The ones left to execute_statement get the hook there
+            if !matches!(
+                stmt,
+                Stmt::VariableAssign { .. }
+                    | Stmt::Constant { .. }
+                    | Stmt::Expression { .. }
+                    | Stmt::DestructureAssign { .. }
+                    | Stmt::Import { .. }
+                    | Stmt::Function { .. }
+                    | Stmt::Class { .. }
+                    | Stmt::Enum { .. }
+            ) {
+                self.run_hook(&stmt);
+            }
+
+            match stmt {
+                Stmt::Yield { value, span } => {
+                    self.span = span;
+
+                    return Ok(Some(self.evaluate_expression(value)?));
+                }
+                Stmt::Return { value, span } => {
+                    self.span = span;
+                    self.evaluate_expression(value)?;
+
+                    return Ok(None);
+                }
+                Stmt::Continue { span } => {
+                    self.span = span;
+
+                    // Drop everything inside the innermost loop and let it move on
+                    loop {
+                        let depth = frames.len();
+
+                        match frames.last_mut() {
+                            Some(Frame::While { body, pc, .. }) | Some(Frame::ForLoop { body, pc, .. }) => {
+                                *pc = body.len();
+                                break;
+                            }
+                            // A ghost outside of any loop does nothing, like in a normal cookable
+                            Some(Frame::Block { .. }) if depth > 1 => {
+                                frames.pop();
+                            }
+                            _ => break,
+                        }
+                    }
+                }
+                Stmt::If {
+                    condition,
+                    then_branch,
+                    else_branch,
+                    span,
+                } => {
+                    self.span = span;
+
+                    if self.condition_holds(condition)? {
+                        frames.push(Frame::Block {
+                            body: then_branch,
+                            pc: 0,
+                        });
+                    } else if let Some(else_branch) = else_branch {
+                        frames.push(Frame::Block {
+                            body: else_branch,
+                            pc: 0,
+                        });
+                    }
+                }
+                Stmt::While {
+                    condition,
+                    body,
+                    span,
+                } => {
+                    self.span = span;
+
+                    let pc = body.len();
+                    frames.push(Frame::While {
+                        condition,
+                        body,
+                        pc,
+                    });
+                }
+                Stmt::ForLoop {
+                    iterators,
+                    collection,
+                    body,
+                    span,
+                } => {
+                    self.span = span;
+
+                    let collection = self.evaluate_expression(collection)?;
+                    let items = self.loop_items(collection)?;
+
+                    let pc = body.len();
+                    frames.push(Frame::ForLoop {
+                        iterators,
+                        items,
+                        body,
+                        pc,
+                    });
+                }
+                Stmt::Match {
+                    subject,
+                    arms,
+                    span,
+                } => {
+                    self.span = span;
+
+                    let subject = self.evaluate_expression(subject)?;
+
+                    for arm in arms {
+                        if self.match_pattern(&arm.pattern, &subject)? {
+                            frames.push(Frame::Block {
+                                body: arm.body,
+                                pc: 0,
+                            });
+                            break;
+                        }
+                    }
+                }
+                // Everything else can't suspend, so the normal path runs it
+                stmt => {
+                    self.execute_statement(stmt)?;
+                }
+            }
+        }
+    }
+}
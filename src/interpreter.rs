@@ -1,27 +1,99 @@
 // interpreter.rs
 use crate::{
     error,
-    lexer::Token,
+    generator::{self, Generator},
+    lexer::{Span, Token},
     libs::{self, Library},
-    parser::{Expr, Stmt},
+    parser::{EnumVariant, Expr, Param, Pattern, Stmt, WaitFor},
+    range::RangeIter,
+    suggest,
 };
-use {std::collections::HashMap, std::io::Write};
+use num_bigint::BigInt;
+use {
+    std::cmp::Ordering,
+    std::collections::{hash_map::Entry, HashMap},
+    std::io::Write,
+};
+
+// Something that wants a look before every statement runs, like the debugger
+pub trait Hook {
+    fn before_statement(&mut self, interpreter: &mut Interpreter, stmt: &Stmt);
+}
+
+// Handled in execute_function_call, before user functions are looked at
+pub const BUILTINS: &[&str] = &["aura", "yap", "gimme", "thicc", "listmaxx", "attemptrizz"];
 
 pub struct Interpreter {
     pub variables: HashMap<String, Expr>,
-    pub functions: HashMap<String, Vec<Stmt>>,
+    pub locals: Vec<HashMap<String, Expr>>, // One frame of parameters per active function call
+    pub functions: HashMap<String, UserFunction>,
     pub classes: HashMap<String, ClassDefinition>,
+    pub enums: HashMap<String, Vec<EnumVariant>>,
     pub instances: HashMap<String, Instance>, // Instance ID, new Class()
     pub libs: HashMap<String, Library>,
+    pub generators: HashMap<usize, Generator>,
+    pub next_generator_id: usize,
+    pub constants: HashMap<String, Span>, // Constant name, where it was declared
 
     // Live runtime info
     pub current_instance: Option<String>, // id of the current instance
-    pub line: usize,
+    pub printing: Vec<String>,            // ids of the instances being yapped right now
+    pub strict: bool,                     // Conditions have to be sigma or ohio, nothing else
+    pub span: Span,
+    pub calls: Vec<error::TraceFrame>, // The cookables running right now, outermost first
+    pub generator_frame: Option<usize>, // Which of the locals belongs to the generator running right now
+
+    pub hook: Option<Box<dyn Hook>>,
+    pub output: Box<dyn Write>, // Where yap goes, stdout unless the debug adapter needs it
+    pub stdin: bool,            // attemptrizz can read stdin, not when the debug adapter talks over it
+}
+
+#[derive(Debug, Clone)]
+pub struct UserFunction {
+    pub name: String,
+    pub params: Vec<Param>,
+    pub body: Vec<Stmt>,
+    pub is_generator: bool, // Has a yeet somewhere in the body
+    pub class: Option<String>, // The pookie it belongs to, for methods
+}
+
+// Both sides as big numbers, only when at least one of them already is one
+fn big_operands(left: &Expr, right: &Expr) -> Option<(BigInt, BigInt)> {
+    match (left, right) {
+        (Expr::BigNumber(l), Expr::BigNumber(r)) => Some((l.clone(), r.clone())),
+        (Expr::BigNumber(l), Expr::Number(r)) => Some((l.clone(), BigInt::from(*r))),
+        (Expr::Number(l), Expr::BigNumber(r)) => Some((BigInt::from(*l), r.clone())),
+        _ => None,
+    }
+}
+
+// Division rounds towards zero, the same as it does for i64
+fn big_arithmetic(left: BigInt, op: &Token, right: BigInt) -> Expr {
+    Expr::whole_number(match op {
+        Token::Plus => left + right,
+        Token::Minus => left - right,
+        Token::Star => left * right,
+        _ => left / right,
+    })
+}
+
+impl UserFunction {
+    pub fn new(name: String, params: Vec<Param>, body: Vec<Stmt>) -> Self {
+        let is_generator = generator::body_yields(&body);
+
+        UserFunction {
+            name,
+            params,
+            body,
+            is_generator,
+            class: None,
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct ClassDefinition {
-    pub functions: HashMap<String, Vec<Stmt>>, // Method name to statements
+    pub functions: HashMap<String, UserFunction>, // Method name to function
 }
 
 #[derive(Debug)]
@@ -32,15 +104,14 @@ pub struct Instance {
 
 #[derive(Debug)]
 pub struct Class {
-    pub functions: HashMap<String, Vec<Stmt>>,
+    pub functions: HashMap<String, UserFunction>,
     pub variables: HashMap<String, Expr>,
     pub class_name: String,
     pub instance_id: String,
-    pub is_self: bool,
 }
 
 #[derive(Debug, PartialEq)]
-enum ControlFlow {
+pub enum ControlFlow {
     Continue,
     Return(Expr),
     None,
@@ -50,16 +121,28 @@ impl Interpreter {
     pub fn new() -> Self {
         Interpreter {
             variables: HashMap::new(),
+            locals: Vec::new(),
             functions: HashMap::new(),
             classes: HashMap::new(),
+            enums: HashMap::new(),
             instances: HashMap::new(),
             libs: HashMap::new(),
+            generators: HashMap::new(),
+            next_generator_id: 0,
+            constants: HashMap::new(),
             current_instance: None,
-            line: 0,
+            printing: Vec::new(),
+            strict: false,
+            span: Span::default(),
+            calls: Vec::new(),
+            generator_frame: None,
+            hook: None,
+            output: Box::new(std::io::stdout()),
+            stdin: true,
         }
     }
 
-    pub fn interpret(&mut self, statements: Vec<Stmt>) -> Result<(), error::ParseError> {
+    pub fn interpret(&mut self, statements: Vec<Stmt>) -> Result<(), error::RuntimeError> {
         for stmt in statements {
             self.execute_statement(stmt)?;
         }
@@ -67,14 +150,24 @@ impl Interpreter {
         Ok(())
     }
 
-    fn execute_statement(&mut self, stmt: Stmt) -> Result<ControlFlow, error::ParseError> {
+    // Taken out while it runs, so the hook can use the interpreter without running itself again
+    pub fn run_hook(&mut self, stmt: &Stmt) {
+        if let Some(mut hook) = self.hook.take() {
+            hook.before_statement(self, stmt);
+            self.hook = Some(hook);
+        }
+    }
+
+    pub fn execute_statement(&mut self, stmt: Stmt) -> Result<ControlFlow, error::RuntimeError> {
+        self.run_hook(&stmt);
+
         match stmt {
             Stmt::Class {
                 name,
                 functions,
-                line,
+                span,
             } => {
-                self.line = line;
+                self.span = span;
 
                 // Create a new ClassDefinition
                 let class_definition = ClassDefinition {
@@ -84,16 +177,27 @@ impl Interpreter {
                             // If the method is a Function statement, store it
                             if let Stmt::Function {
                                 name: method_name,
+                                params,
                                 body,
                                 ..
                             } = method
                             {
-                                Some((method_name.clone(), body.clone()))
+                                Some((
+                                    method_name.clone(),
+                                    UserFunction {
+                                        class: Some(name.clone()),
+                                        ..UserFunction::new(
+                                            method_name.clone(),
+                                            params.clone(),
+                                            body.clone(),
+                                        )
+                                    },
+                                ))
                             } else {
                                 None // Ignore non-function statements
                             }
                         })
-                        .collect::<HashMap<String, Vec<Stmt>>>(),
+                        .collect::<HashMap<String, UserFunction>>(),
                 };
 
                 // Store the class definition in the classes HashMap
@@ -101,18 +205,26 @@ impl Interpreter {
 
                 Ok(ControlFlow::None)
             }
-            Stmt::Function { name, body, line } => {
-                self.line = line;
-                self.functions.insert(name, body);
+            Stmt::Function {
+                name,
+                params,
+                body,
+                span,
+                ..
+            } => {
+                self.span = span;
+                self.functions
+                    .insert(name.clone(), UserFunction::new(name, params, body));
                 Ok(ControlFlow::None)
             }
             Stmt::VariableAssign {
                 name,
                 object,
                 value,
-                line,
+                span,
+                ..
             } => {
-                self.line = line;
+                self.span = span;
                 let evaluated = self.evaluate_expression(value)?;
 
                 // find out if its a instance variable
@@ -121,15 +233,15 @@ impl Interpreter {
                         Expr::Ident(object_name) if object_name == "goat" => {
                             // Handle the special "goat" case
                             let current_instance = self.current_instance.clone().ok_or_else(|| {
-                                error::ParseError::GeneralError {
-                                    line: self.line,
+                                error::RuntimeError::GeneralError {
+                                    span: self.span,
                                     message: "Cannot use 'self' outside of a method context".to_string(),
                                 }
                             })?;
             
                             let instance = self.instances.get_mut(&current_instance).ok_or_else(|| {
-                                error::ParseError::GeneralError {
-                                    line: self.line,
+                                error::RuntimeError::GeneralError {
+                                    span: self.span,
                                     message: "Current instance not found".to_string(),
                                 }
                             })?;
@@ -140,16 +252,16 @@ impl Interpreter {
                         }
                         Expr::Ident(object_name) => {
                             // Check if the object is a variable
-                            if let Some(value) = self.variables.get(&object_name) {
-                                match value {
+                            if let Some(value) = self.lookup_variable(&object_name).cloned() {
+                                match &value {
                                     Expr::Instance {
                                         class_name:_,
                                         instance_id,
                                     } => {
                                         let instance =
                                             self.instances.get_mut(instance_id).ok_or_else(|| {
-                                                error::ParseError::GeneralError {
-                                                    line: self.line,
+                                                error::RuntimeError::GeneralError {
+                                                    span: self.span,
                                                     message: format!(
                                                         "Instance not found for ID: {}",
                                                         instance_id
@@ -161,40 +273,97 @@ impl Interpreter {
 
                                         Ok(ControlFlow::None)
                                     }
-                                    _ => Err(error::ParseError::GeneralError {
-                                        line: self.line,
+                                    _ => Err(error::RuntimeError::GeneralError {
+                                        span: self.span,
                                         message: "Variable definitions are only supported for instance variables."
                                             .to_string(),
                                     }),
                                 }
                             } else {
-                                Err(error::ParseError::GeneralError {
-                                    line: self.line,
-                                    message: format!("Variable not found: {}", object_name),
+                                Err(error::RuntimeError::UnknownVariable {
+                                    suggestion: self.suggest_variable(&object_name),
+                                    name: object_name,
+                                    span: self.span,
                                 })
                             }
                         }
-                        _ => Err(error::ParseError::GeneralError {
-                            line: self.line,
+                        _ => Err(error::RuntimeError::GeneralError {
+                            span: self.span,
                             message: "Object definitions of other types than IDENT are not supported".to_string(),
                         }),
                     }
                 } else {
                     // Its a normal global variable
-                    self.variables.insert(name, evaluated);
-
+                    self.assign_variable(name, evaluated)?;
 
                     Ok(ControlFlow::None)
                 }
             }
+            Stmt::DestructureAssign { names, value, span } => {
+                self.span = span;
+
+                let evaluated = self.evaluate_expression(value)?;
+
+                for (name, value) in names.iter().zip(self.unpack_values(evaluated, names.len())?) {
+                    self.assign_variable(name.clone(), value)?;
+                }
+
+                Ok(ControlFlow::None)
+            }
+            Stmt::ForLoop {
+                iterators,
+                collection,
+                body,
+                span,
+            } => {
+                self.span = span;
+
+                // Ranges and generators are walked lazily, lists are already there
+                let collection = self.evaluate_expression(collection)?;
+                let mut items = self.loop_items(collection)?;
+
+                while let Some(item) = self.next_item(&mut items)? {
+                    self.bind_iterators(&iterators, item)?;
+
+                    for stmt in &body {
+                        match self.execute_statement(stmt.clone())? {
+                            ControlFlow::Continue => break, // Skip to the next iteration
+                            ControlFlow::Return(value) => return Ok(ControlFlow::Return(value)),
+                            ControlFlow::None => {} // Continue executing the next statement
+                        }
+                    }
+                }
+
+                Ok(ControlFlow::None)
+            }
+            Stmt::Constant {
+                name, value, span, ..
+            } => {
+                self.span = span;
+
+                if let Some(declared_at) = self.constants.get(&name) {
+                    return Err(error::RuntimeError::ConstantReassign {
+                        name,
+                        declared_at: *declared_at,
+                        span: self.span,
+                    });
+                }
+
+                let evaluated = self.evaluate_expression(value)?;
+
+                self.variables.insert(name.clone(), evaluated);
+                self.constants.insert(name, span);
+
+                Ok(ControlFlow::None)
+            }
             Stmt::While {
                 condition,
                 body,
-                line,
+                span,
             } => {
-                self.line = line;
+                self.span = span;
 
-                while self.evaluate_expression(condition.clone())? != Expr::Boolean(false) {
+                while self.condition_holds(condition.clone())? {
                     for stmt in &body {
                         match self.execute_statement(stmt.clone())? {
                             ControlFlow::Continue => break, // Skip to the next iteration
@@ -210,11 +379,11 @@ impl Interpreter {
                 condition,
                 then_branch,
                 else_branch,
-                line,
+                span,
             } => {
-                self.line = line;
+                self.span = span;
 
-                if self.evaluate_expression(condition)? != Expr::Boolean(false) {
+                if self.condition_holds(condition)? {
                     for stmt in then_branch {
                         match self.execute_statement(stmt)? {
                             ControlFlow::Continue => return Ok(ControlFlow::Continue),
@@ -234,63 +403,110 @@ impl Interpreter {
 
                 Ok(ControlFlow::None)
             }
-            Stmt::Expression { value: expr, line } => {
-                self.line = line;
+            Stmt::Expression { value: expr, span } => {
+                self.span = span;
                 self.evaluate_expression(expr)?;
                 Ok(ControlFlow::None)
             }
-            Stmt::Import { library, line } => {
-                self.line = line;
+            Stmt::Import { library, span } => {
+                self.span = span;
 
-                match library.as_str() {
-                    libs::nerd::LIBRARY_NAME => {
-                        self.libs.insert(library, libs::nerd::load_nerd_library());
+                match libs::load_library(&library) {
+                    Some(lib) => {
+                        self.libs.insert(library, lib);
                     }
-                    libs::skui::LIBRARY_NAME => {
-                        self.libs.insert(library, libs::skui::load_skui_library());
+                    None => {
+                        return Err(error::RuntimeError::UnknownLibrary {
+                            suggestion: suggest::did_you_mean(&library, libs::LIBRARY_NAMES.iter().copied()),
+                            name: library,
+                            span: self.span,
+                        });
                     }
-                    libs::apel::LIBRARY_NAME => {
-                        self.libs.insert(library, libs::apel::load_apel_library());
+                }
+
+                Ok(ControlFlow::None)
+            }
+            Stmt::Enum {
+                name,
+                variants,
+                span,
+            } => {
+                self.span = span;
+                self.enums.insert(name, variants);
+
+                Ok(ControlFlow::None)
+            }
+            Stmt::Match {
+                subject,
+                arms,
+                span,
+            } => {
+                self.span = span;
+
+                let subject = self.evaluate_expression(subject)?;
+
+                for arm in arms {
+                    if !self.match_pattern(&arm.pattern, &subject)? {
+                        continue;
                     }
-                    _ => {
-                        return Err(error::ParseError::GeneralError {
-                            line: self.line,
-                            message: format!("Unknown library gyatt import: {}", library),
-                        });
+
+                    for stmt in arm.body {
+                        match self.execute_statement(stmt)? {
+                            ControlFlow::Continue => return Ok(ControlFlow::Continue),
+                            ControlFlow::Return(value) => return Ok(ControlFlow::Return(value)),
+                            ControlFlow::None => {} // Continue executing the next statement
+                        }
                     }
+
+                    break;
                 }
 
                 Ok(ControlFlow::None)
             }
-            Stmt::Return { value, line } => {
-                self.line = line;
+            Stmt::Return { value, span } => {
+                self.span = span;
                 let return_value = self.evaluate_expression(value)?;
                 Ok(ControlFlow::Return(return_value))
             }
-            Stmt::Continue { line } => {
-                self.line = line;
+            Stmt::Continue { span } => {
+                self.span = span;
                 Ok(ControlFlow::Continue)
             }
-            _ => Err(error::ParseError::GeneralError {
-                line: self.line,
-                message: "Unsupported statement".to_string(),
-            }),
+            // Generator bodies are run by generator.rs, so this is a yeet outside of a cookable
+            Stmt::Yield { span, .. } => {
+                self.span = span;
+
+                Err(error::RuntimeError::GeneralError {
+                    span: self.span,
+                    message: "yeet can only be used inside a cookable".to_string(),
+                })
+            }
         }
     }
 
-    pub fn evaluate_expression(&mut self, expr: Expr) -> Result<Expr, error::ParseError> {
+    pub fn evaluate_expression(&mut self, expr: Expr) -> Result<Expr, error::RuntimeError> {
         match expr {
+            // Errors from inside point at the innermost expression that failed
+            Expr::Spanned { expr, span } => {
+                let outer = std::mem::replace(&mut self.span, span);
+                let result = self.evaluate_expression(*expr);
+                self.span = outer;
+
+                result
+            }
             Expr::Ident(name) => {
-                if let Some(value) = self.variables.get(&name) {
+                if let Some(value) = self.lookup_variable(&name) {
                     Ok(value.clone())
                 } else {
-                    Err(error::ParseError::UnknownVariable {
+                    Err(error::RuntimeError::UnknownVariable {
+                        suggestion: self.suggest_variable(&name),
                         name,
-                        line: self.line,
+                        span: self.span,
                     })
                 }
             }
             Expr::Number(value) => Ok(Expr::Number(value)),
+            Expr::BigNumber(value) => Ok(Expr::BigNumber(value)),
             Expr::StringLiteral(string) => Ok(Expr::StringLiteral(string)), // Handle strings differently if neded
             Expr::Boolean(value) => Ok(Expr::Boolean(value)),
             // Expr::None => Ok(Expr::None),
@@ -305,41 +521,61 @@ impl Interpreter {
 
                 Ok(Expr::List(evaluated_values))
             }
+            // and/or only look at the right side when they have to
+            Expr::BinOp {
+                left,
+                op: Token::And,
+                right,
+            } => Ok(Expr::Boolean(self.condition_holds(*left)? && self.condition_holds(*right)?)),
+            Expr::BinOp {
+                left,
+                op: Token::Or,
+                right,
+            } => Ok(Expr::Boolean(self.condition_holds(*left)? || self.condition_holds(*right)?)),
+            Expr::Not(value) => Ok(Expr::Boolean(!self.condition_holds(*value)?)),
             Expr::BinOp { left, op, right } => {
                 let left_val = self.evaluate_expression(*left)?;
                 let right_val = self.evaluate_expression(*right)?;
 
+                // Pookie instances can bring their own operators
+                if let Some(value) = self.call_operator_method(&op, &left_val, &right_val)? {
+                    return Ok(value);
+                }
+
+                // Once a number has gone past 64 bits everything it touches is worked out the slow way
+                if let Some((l, r)) = big_operands(&left_val, &right_val) {
+                    if matches!(op, Token::Plus | Token::Minus | Token::Star | Token::Slash) {
+                        if op == Token::Slash && r == BigInt::ZERO {
+                            return Err(error::RuntimeError::DivisionByZero { span: self.span });
+                        }
+
+                        return Ok(big_arithmetic(l, &op, r));
+                    }
+                }
+
                 // Handle different types of binary operations
                 match op {
                     Token::Plus => match (left_val.clone(), right_val.clone()) {
-                        (Expr::Number(l), Expr::Number(r)) => Ok(Expr::Number(l + r)),
+                        (Expr::Number(l), Expr::Number(r)) => self.checked_arithmetic(l, op, r),
                         (Expr::StringLiteral(l), Expr::StringLiteral(r)) => {
                             Ok(Expr::StringLiteral(l + r.as_str()))
                         }
-                        _ => Err(error::ParseError::TypeError {
-                            expected: Expr::Number(-1),
-                            found: Expr::BinOp {
-                                left: Box::new(left_val),
-                                op,
-                                right: Box::new(right_val),
-                            },
-                            line: self.line,
+                        _ => Err(error::RuntimeError::TypeError {
+                            expected: "number".to_string(),
+                            found: format!("{} {} {}", left_val.type_name(), op, right_val.type_name()),
+                            span: self.span,
                         }),
                     },
                     Token::Minus => {
                         if let (Expr::Number(l), Expr::Number(r)) =
                             (left_val.clone(), right_val.clone())
                         {
-                            Ok(Expr::Number(l - r))
+                            self.checked_arithmetic(l, op, r)
                         } else {
-                            Err(error::ParseError::TypeError {
-                                expected: Expr::Number(-1),
-                                found: Expr::BinOp {
-                                    left: Box::new(left_val),
-                                    op,
-                                    right: Box::new(right_val),
-                                },
-                                line: self.line,
+                            Err(error::RuntimeError::TypeError {
+                                expected: "number".to_string(),
+                                found: format!("{} {} {}", left_val.type_name(), op, right_val.type_name()),
+                                span: self.span,
                             })
                         }
                     }
@@ -347,16 +583,12 @@ impl Interpreter {
                         if let (Expr::Number(l), Expr::Number(r)) =
                             (left_val.clone(), right_val.clone())
                         {
-                            Ok(Expr::Number(l * r))
+                            self.checked_arithmetic(l, op, r)
                         } else {
-                            Err(error::ParseError::TypeError {
-                                expected: Expr::Number(-1),
-                                found: Expr::BinOp {
-                                    left: Box::new(left_val),
-                                    op,
-                                    right: Box::new(right_val),
-                                },
-                                line: self.line,
+                            Err(error::RuntimeError::TypeError {
+                                expected: "number".to_string(),
+                                found: format!("{} {} {}", left_val.type_name(), op, right_val.type_name()),
+                                span: self.span,
                             })
                         }
                     }
@@ -365,89 +597,63 @@ impl Interpreter {
                             (left_val.clone(), right_val.clone())
                         {
                             if r == 0 {
-                                Err(error::ParseError::DivisionByZero { line: self.line })
+                                Err(error::RuntimeError::DivisionByZero { span: self.span })
                             } else {
-                                Ok(Expr::Number(l / r))
+                                self.checked_arithmetic(l, op, r)
                             }
                         } else {
-                            Err(error::ParseError::TypeError {
-                                expected: Expr::Number(-1),
-                                found: Expr::BinOp {
-                                    left: Box::new(left_val),
-                                    op,
-                                    right: Box::new(right_val),
-                                },
-                                line: self.line,
+                            Err(error::RuntimeError::TypeError {
+                                expected: "number".to_string(),
+                                found: format!("{} {} {}", left_val.type_name(), op, right_val.type_name()),
+                                span: self.span,
                             })
                         }
                     }
-                    Token::Rizz => match (left_val.clone(), right_val.clone()) {
-                        (Expr::Number(l), Expr::Number(r)) => Ok(Expr::Boolean(l == r)),
-                        (Expr::StringLiteral(l), Expr::StringLiteral(r)) => {
-                            Ok(Expr::Boolean(l == r.as_str()))
+                    Token::Rizz => Ok(Expr::Boolean(self.values_equal(&left_val, &right_val)?)),
+                    Token::GreaterThan | Token::LessThan => {
+                        match self.compare_values(&left_val, &right_val)? {
+                            Some(ordering) if op == Token::GreaterThan => {
+                                Ok(Expr::Boolean(ordering == Ordering::Greater))
+                            }
+                            Some(ordering) => Ok(Expr::Boolean(ordering == Ordering::Less)),
+                            None => Err(error::RuntimeError::TypeError {
+                                expected: "bool".to_string(),
+                                found: format!("{} {} {}", left_val.type_name(), op, right_val.type_name()),
+                                span: self.span,
+                            }),
                         }
-                        _ => Err(error::ParseError::TypeError {
-                            expected: Expr::Boolean(false),
-                            found: Expr::BinOp {
-                                left: Box::new(left_val),
-                                op,
-                                right: Box::new(right_val),
-                            },
-                            line: self.line,
-                        }),
-                    },
-                    Token::GreaterThan => match (left_val.clone(), right_val.clone()) {
-                        (Expr::Number(l), Expr::Number(r)) => Ok(Expr::Boolean(l > r)),
-                        _ => Err(error::ParseError::TypeError {
-                            expected: Expr::Boolean(false),
-                            found: Expr::BinOp {
-                                left: Box::new(left_val),
-                                op,
-                                right: Box::new(right_val),
-                            },
-                            line: self.line,
-                        }),
-                    },
-                    Token::LessThan => match (left_val.clone(), right_val.clone()) {
-                        (Expr::Number(l), Expr::Number(r)) => Ok(Expr::Boolean(l < r)),
-                        _ => Err(error::ParseError::TypeError {
-                            expected: Expr::Boolean(false),
-                            found: Expr::BinOp {
-                                left: Box::new(left_val),
-                                op,
-                                right: Box::new(right_val),
-                            },
-                            line: self.line,
-                        }),
-                    },
+                    }
                     // Add other operators as needed
-                    _ => Err(error::ParseError::GeneralError {
-                        line: self.line,
-                        message: format!("Unsupported operator: {:?}", op),
+                    _ => Err(error::RuntimeError::GeneralError {
+                        span: self.span,
+                        message: format!("Unsupported operator: {}", op),
                     }),
                 }
             }
             Expr::ObjectValue { object, name } => {
+                if let Some(enum_name) = self.enum_name_of(&object) {
+                    return self.make_enum_variant(enum_name, name, Vec::new());
+                }
+
                 let class = self.get_class_from_object(&*object)?;
 
                 if let Some(value) = class.variables.get(&name) {
                     Ok(value.clone())
                 } else {
-                    Err(error::ParseError::UnknownVariable {
-                        name,
-                        line: self.line,
+                    Err(error::RuntimeError::UnknownVariable {
+                        suggestion: suggest::did_you_mean(&name, class.variables.keys().map(String::as_str)),
+                        name: format!("{}.{}", class.class_name, name),
+                        span: self.span,
                     })
                 }
             }
-            Expr::NewInstance {
-                class_name,
-                args: _,
-            } => {
+            Expr::NewInstance { class_name, args } => {
                 // Look up the class definition
                 let class_def = self.classes.get(&class_name).ok_or_else(|| {
-                    error::ParseError::GeneralError {
-                        line: self.line,
-                        message: format!("Unknown class: {}", class_name),
+                    error::RuntimeError::UnknownVariable {
+                        name: class_name.clone(),
+                        suggestion: suggest::did_you_mean(&class_name, self.classes.keys().map(String::as_str)),
+                        span: self.span,
                     }
                 })?;
 
@@ -470,29 +676,25 @@ impl Interpreter {
                     class_name: class_name.clone(),
                 };
 
-                self.instances.insert(instance_id.clone(), instance);
+                let init = class_def.functions.get("__edge__").cloned().ok_or_else(|| {
+                    error::RuntimeError::UnknownFunction {
+                        name: format!("{}.__edge__", class_name),
+                        suggestion: None,
+                        span: self.span,
+                    }
+                })?;
 
-                // set the current instance
-                self.current_instance = Some(instance_id.clone());
+                // Arguments belong to the caller, so evaluate them before goat changes
+                let args = self.evaluate_arguments(args)?;
 
-                 // Run the init __edge__ function
-                 if let Some(body) = class_def.functions.get("__edge__") {
-                    for stmt in body.clone() {
-                        let controlflow = self.execute_statement(stmt)?;
+                self.instances.insert(instance_id.clone(), instance);
 
-                        match controlflow {
-                            ControlFlow::Return(value) => return Ok(value),
-                            _ => {}
-                        }
-                    }
-                } else {
-                    return Err(error::ParseError::UnknownFunction {
-                        name: "__edge__".to_string(),
-                        line: self.line,
-                    });
-                }
+                // Run the init __edge__ function with the current instance set
+                let previous_instance = self.current_instance.replace(instance_id.clone());
+                let result = self.call_function(&init, args);
+                self.current_instance = previous_instance;
 
-                self.current_instance = None;
+                result?;
 
                 // Return the instance ID as an expression
                 Ok(Expr::Instance {
@@ -500,59 +702,625 @@ impl Interpreter {
                     instance_id,
                 })
             }
-            _ => Err(error::ParseError::GeneralError {
-                line: self.line,
-                message: format!("Unsupported expression {:?}", expr),
+            // Already evaluated, e.g. an instance passed in as an argument
+            Expr::Instance { .. }
+            | Expr::EnumVariant { .. }
+            | Expr::Generator { .. }
+            | Expr::Wait(_) => Ok(expr),
+            Expr::Range {
+                start,
+                end,
+                step,
+                inclusive,
+            } => {
+                let start = self.expr_to_number(*start)?;
+                let end = self.expr_to_number(*end)?;
+                let step = match step {
+                    Some(step) => self.expr_to_number(*step)?,
+                    None => 1,
+                };
+
+                if step == 0 {
+                    return Err(error::RuntimeError::GeneralError {
+                        span: self.span,
+                        message: "Range step cannot be 0".to_string(),
+                    });
+                }
+
+                Ok(Expr::Range {
+                    start: Box::new(Expr::Number(start)),
+                    end: Box::new(Expr::Number(end)),
+                    step: Some(Box::new(Expr::Number(step))),
+                    inclusive,
+                })
+            }
+        }
+    }
+
+    // What counts as true in sus, skibidi, and, or and not:
+    //   ohio, 0, "", [] and empty ranges are false
+    //   everything else is true, including every pookie, vibe and generator
+    // In strict mode only sigma and ohio are allowed at all
+    pub fn is_truthy(&mut self, value: &Expr) -> Result<bool, error::RuntimeError> {
+        match value {
+            Expr::Boolean(value) => Ok(*value),
+            _ if self.strict => Err(error::RuntimeError::TypeError {
+                expected: "bool".to_string(),
+                found: value.type_name(),
+                span: self.span,
             }),
+            Expr::Number(value) => Ok(*value != 0),
+            Expr::BigNumber(_) => Ok(true), // Never 0, that would be a plain Number
+            Expr::StringLiteral(value) => Ok(!value.is_empty()),
+            Expr::List(items) => Ok(!items.is_empty()),
+            range @ Expr::Range { .. } => Ok(self.range_iter(range)?.next().is_some()),
+            _ => Ok(true),
         }
     }
 
-    pub fn expr_to_string(&mut self, expr: Expr) -> Result<String, error::ParseError> {
-        match self.evaluate_expression(expr)? {
+    pub fn condition_holds(&mut self, condition: Expr) -> Result<bool, error::RuntimeError> {
+        let value = self.evaluate_expression(condition)?;
+
+        self.is_truthy(&value)
+    }
+
+    pub fn expr_to_string(&mut self, expr: Expr) -> Result<String, error::RuntimeError> {
+        let value = self.evaluate_expression(expr)?;
+
+        self.format_value(value, false)
+    }
+
+    // Strings only get quotes when they're inside something else, so yap("hi") is still hi
+    pub fn format_value(&mut self, value: Expr, nested: bool) -> Result<String, error::RuntimeError> {
+        match value {
+            Expr::Instance {
+                class_name,
+                instance_id,
+            } => self.format_instance(class_name, instance_id),
             Expr::Number(value) => Ok(value.to_string()),
+            Expr::BigNumber(value) => Ok(value.to_string()),
+            Expr::StringLiteral(value) if nested => Ok(format!("{:?}", value)),
             Expr::StringLiteral(value) => Ok(value),
             Expr::Boolean(value) => Ok(if value {
                 "sigma".to_string()
             } else {
                 "ohio".to_string()
             }),
+            Expr::List(items) => {
+                let mut values = Vec::new();
+                for item in items {
+                    values.push(self.format_value(item, true)?);
+                }
+
+                Ok(format!("[{}]", values.join(", ")))
+            }
+            Expr::EnumVariant {
+                enum_name,
+                variant,
+                payload,
+            } => {
+                if payload.is_empty() {
+                    return Ok(format!("{}.{}", enum_name, variant));
+                }
+
+                let mut values = Vec::new();
+                for value in payload {
+                    values.push(self.format_value(value, true)?);
+                }
+
+                Ok(format!("{}.{}({})", enum_name, variant, values.join(", ")))
+            }
+            Expr::Generator { function, .. } => Ok(format!("<generator {}>", function)),
+            Expr::Wait(WaitFor::Frames(frames)) => Ok(format!("<wait {} frames>", frames)),
+            Expr::Wait(WaitFor::Millis(millis)) => Ok(format!("<wait {}ms>", millis)),
+            range @ Expr::Range { .. } => {
+                let (start, end, step, inclusive) = self.range_bounds(&range)?;
+                let dots = if inclusive { "..=" } else { ".." };
+
+                if step == 1 {
+                    Ok(format!("{}{}{}", start, dots, end))
+                } else {
+                    Ok(format!("{}{}{} by {}", start, dots, end, step))
+                }
+            }
             _ => Ok("".to_string()), // Return an empty string for other types
         }
     }
 
-    pub fn expr_to_number(&mut self, expr: Expr) -> Result<i64, error::ParseError> {
-        match self.evaluate_expression(expr.clone())? {
+    // Instances can point back at each other, so one that's already being yapped further up
+    // is cut short instead of recursing forever
+    fn format_instance(&mut self, class_name: String, instance_id: String) -> Result<String, error::RuntimeError> {
+        if self.printing.contains(&instance_id) {
+            return Ok(format!("{}{{...}}", class_name));
+        }
+
+        self.printing.push(instance_id.clone());
+        let result = self.format_instance_fields(&class_name, &instance_id);
+        self.printing.pop();
+
+        result
+    }
+
+    fn format_instance_fields(&mut self, class_name: &str, instance_id: &str) -> Result<String, error::RuntimeError> {
+        if self.find_method(class_name, "__yap__").is_some() {
+            let value = self.call_method(class_name, instance_id, "__yap__", Vec::new())?;
+
+            return self.format_value(value, false);
+        }
+
+        let mut fields: Vec<(String, Expr)> = match self.instances.get(instance_id) {
+            Some(instance) => instance
+                .variables
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+            None => Vec::new(),
+        };
+        fields.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut values = Vec::new();
+        for (name, value) in fields {
+            values.push(format!("{}: {}", name, self.format_value(value, true)?));
+        }
+
+        Ok(format!("{}{{{}}}", class_name, values.join(", ")))
+    }
+
+    // Plain i64 maths while it fits, a sum that doesn't becomes a big number instead of wrapping
+    fn checked_arithmetic(&self, left: i64, op: Token, right: i64) -> Result<Expr, error::RuntimeError> {
+        let result = match op {
+            Token::Plus => left.checked_add(right),
+            Token::Minus => left.checked_sub(right),
+            Token::Star => left.checked_mul(right),
+            _ => left.checked_div(right),
+        };
+
+        Ok(match result {
+            Some(value) => Expr::Number(value),
+            None => big_arithmetic(BigInt::from(left), &op, BigInt::from(right)),
+        })
+    }
+
+    // For everything that needs an i64, like range ends and library arguments
+    pub fn expr_to_number(&mut self, expr: Expr) -> Result<i64, error::RuntimeError> {
+        match self.evaluate_expression(expr)? {
             Expr::Number(value) => Ok(value),
-            _ => Err(error::ParseError::TypeError {
-                expected: Expr::Number(-1),
-                found: expr.clone(),
-                line: self.line,
+            Expr::BigNumber(value) => Err(error::RuntimeError::NumberTooBig {
+                value: value.to_string(),
+                span: self.span,
+            }),
+            found => Err(error::RuntimeError::TypeError {
+                expected: "number".to_string(),
+                found: found.type_name(),
+                span: self.span,
             }),
         }
     }
 
+    pub fn find_method(&self, class_name: &str, method: &str) -> Option<UserFunction> {
+        self.classes
+            .get(class_name)
+            .and_then(|class_def| class_def.functions.get(method))
+            .cloned()
+    }
+
+    // Runs a method with goat set to the instance, args should already be evaluated
+    pub fn call_method(
+        &mut self,
+        class_name: &str,
+        instance_id: &str,
+        method: &str,
+        args: Vec<Expr>,
+    ) -> Result<Expr, error::RuntimeError> {
+        let func = self.find_method(class_name, method).ok_or_else(|| {
+            error::RuntimeError::UnknownFunction {
+                name: format!("{}.{}", class_name, method),
+                suggestion: self.classes.get(class_name).and_then(|class| {
+                    suggest::did_you_mean(method, class.functions.keys().map(String::as_str))
+                }),
+                span: self.span,
+            }
+        })?;
+
+        let previous_instance = self.current_instance.replace(instance_id.to_string());
+        let result = self.call_function(&func, args);
+        self.current_instance = previous_instance;
+
+        result
+    }
+
+    // __plus__, __minus__, __times__, __rizz__ and __lessthan__, None if the operands don't have one
+    fn call_operator_method(
+        &mut self,
+        op: &Token,
+        left: &Expr,
+        right: &Expr,
+    ) -> Result<Option<Expr>, error::RuntimeError> {
+        let method = match op {
+            Token::Plus => "__plus__",
+            Token::Minus => "__minus__",
+            Token::Star => "__times__",
+            Token::Rizz => "__rizz__",
+            Token::LessThan | Token::GreaterThan => "__lessthan__",
+            _ => return Ok(None),
+        };
+
+        // a > b is b < a, so the right side gets asked
+        let (receiver, other) = if *op == Token::GreaterThan {
+            (right, left)
+        } else {
+            (left, right)
+        };
+
+        if let Expr::Instance {
+            class_name,
+            instance_id,
+        } = receiver
+        {
+            if self.find_method(class_name, method).is_some() {
+                let value = self.call_method(class_name, instance_id, method, vec![other.clone()])?;
+                return Ok(Some(value));
+            }
+        }
+
+        // Equality goes both ways, so an instance on the right can answer too
+        if *op == Token::Rizz {
+            if let Expr::Instance {
+                class_name,
+                instance_id,
+            } = right
+            {
+                if self.find_method(class_name, method).is_some() {
+                    let value = self.call_method(class_name, instance_id, method, vec![left.clone()])?;
+                    return Ok(Some(value));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    // rizz for any two values. Lists and enum payloads are compared item by item, pookies are
+    // the same only if they're the same instance (unless they have a __rizz__), and values of
+    // different types are just not equal
+    pub fn values_equal(&mut self, left: &Expr, right: &Expr) -> Result<bool, error::RuntimeError> {
+        if let Some(value) = self.call_operator_method(&Token::Rizz, left, right)? {
+            return Ok(value != Expr::Boolean(false));
+        }
+
+        match (left, right) {
+            (Expr::List(l), Expr::List(r)) => {
+                if l.len() != r.len() {
+                    return Ok(false);
+                }
+
+                for (l, r) in l.iter().zip(r) {
+                    if !self.values_equal(l, r)? {
+                        return Ok(false);
+                    }
+                }
+
+                Ok(true)
+            }
+            (
+                Expr::EnumVariant {
+                    enum_name: l_enum,
+                    variant: l_variant,
+                    payload: l_payload,
+                },
+                Expr::EnumVariant {
+                    enum_name: r_enum,
+                    variant: r_variant,
+                    payload: r_payload,
+                },
+            ) => {
+                if l_enum != r_enum || l_variant != r_variant {
+                    return Ok(false);
+                }
+
+                self.values_equal(&Expr::List(l_payload.clone()), &Expr::List(r_payload.clone()))
+            }
+            (l @ Expr::Range { .. }, r @ Expr::Range { .. }) => {
+                Ok(self.range_bounds(l)? == self.range_bounds(r)?)
+            }
+            (Expr::Instance { instance_id: l, .. }, Expr::Instance { instance_id: r, .. }) => Ok(l == r),
+            (Expr::Generator { id: l, .. }, Expr::Generator { id: r, .. }) => Ok(l == r),
+            // A big number and a plain one are never the same value
+            (Expr::Number(_), Expr::Number(_))
+            | (Expr::BigNumber(_), Expr::BigNumber(_))
+            | (Expr::StringLiteral(_), Expr::StringLiteral(_))
+            | (Expr::Boolean(_), Expr::Boolean(_))
+            | (Expr::Wait(_), Expr::Wait(_)) => Ok(left == right),
+            _ => Ok(false),
+        }
+    }
+
+    // Ordering for < and >. Numbers by value, strings and lists lexicographically,
+    // None when the two can't be ordered
+    pub fn compare_values(&mut self, left: &Expr, right: &Expr) -> Result<Option<Ordering>, error::RuntimeError> {
+        // Pookies with a __lessthan__ can be ordered too, which matters for lists of them
+        if let Some(less) = self.call_operator_method(&Token::LessThan, left, right)? {
+            if less != Expr::Boolean(false) {
+                return Ok(Some(Ordering::Less));
+            }
+
+            // Neither way round being less only means equal if the other side could be asked
+            return match self.call_operator_method(&Token::LessThan, right, left)? {
+                Some(greater) if greater != Expr::Boolean(false) => Ok(Some(Ordering::Greater)),
+                Some(_) => Ok(Some(Ordering::Equal)),
+                None => Ok(None),
+            };
+        }
+
+        if let Some((l, r)) = big_operands(left, right) {
+            return Ok(Some(l.cmp(&r)));
+        }
+
+        match (left, right) {
+            (Expr::Number(l), Expr::Number(r)) => Ok(Some(l.cmp(r))),
+            (Expr::StringLiteral(l), Expr::StringLiteral(r)) => Ok(Some(l.cmp(r))),
+            (Expr::List(l), Expr::List(r)) => {
+                for (l, r) in l.iter().zip(r) {
+                    if self.values_equal(l, r)? {
+                        continue;
+                    }
+
+                    return self.compare_values(l, r);
+                }
+
+                Ok(Some(l.len().cmp(&r.len())))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    // Start, end, step and inclusive of an already evaluated range
+    fn range_bounds(&self, range: &Expr) -> Result<(i64, i64, i64, bool), error::RuntimeError> {
+        if let Expr::Range {
+            start,
+            end,
+            step,
+            inclusive,
+        } = range
+        {
+            let step = step.as_deref().unwrap_or(&Expr::Number(1));
+
+            if let (Expr::Number(start), Expr::Number(end), Expr::Number(step)) =
+                (start.as_ref(), end.as_ref(), step)
+            {
+                return Ok((*start, *end, *step, *inclusive));
+            }
+        }
+
+        Err(error::RuntimeError::GeneralError {
+            span: self.span,
+            message: format!("Expected an evaluated range, found a {}", range.type_name()),
+        })
+    }
+
+    pub fn range_iter(&self, range: &Expr) -> Result<RangeIter, error::RuntimeError> {
+        let (start, end, step, inclusive) = self.range_bounds(range)?;
+
+        Ok(RangeIter::new(start, end, step, inclusive))
+    }
+
+    // Returns the enum name if the object is a vibe that isn't shadowed by a variable
+    fn enum_name_of(&self, object: &Expr) -> Option<String> {
+        match object {
+            Expr::Ident(name)
+                if self.enums.contains_key(name)
+                    && !self.variables.contains_key(name)
+                    && !self.locals.last().is_some_and(|frame| frame.contains_key(name)) =>
+            {
+                Some(name.clone())
+            }
+            _ => None,
+        }
+    }
+
+    fn make_enum_variant(
+        &mut self,
+        enum_name: String,
+        variant: String,
+        args: Vec<Expr>,
+    ) -> Result<Expr, error::RuntimeError> {
+        let fields = self
+            .enums
+            .get(&enum_name)
+            .and_then(|variants| variants.iter().find(|v| v.name == variant))
+            .map(|v| v.fields.len())
+            .ok_or_else(|| error::RuntimeError::GeneralError {
+                span: self.span,
+                message: format!("Unknown variant: {} on vibe {}", variant, enum_name),
+            })?;
+
+        if args.len() != fields {
+            return Err(error::RuntimeError::ArgumentMismatch {
+                expected: fields,
+                found: args.len(),
+                span: self.span,
+            });
+        }
+
+        let mut payload = Vec::new();
+        for arg in args {
+            payload.push(self.evaluate_expression(arg)?);
+        }
+
+        Ok(Expr::EnumVariant {
+            enum_name,
+            variant,
+            payload,
+        })
+    }
+
+    pub fn match_pattern(&mut self, pattern: &Pattern, subject: &Expr) -> Result<bool, error::RuntimeError> {
+        match pattern {
+            Pattern::Wildcard => Ok(true),
+            Pattern::Value(expr) => {
+                let value = self.evaluate_expression(expr.clone())?;
+
+                self.values_equal(&value, subject)
+            }
+            Pattern::Variant {
+                enum_name,
+                variant,
+                bindings,
+            } => {
+                let payload = match subject {
+                    Expr::EnumVariant {
+                        enum_name: subject_enum,
+                        variant: subject_variant,
+                        payload,
+                    } if subject_enum == enum_name && subject_variant == variant => payload,
+                    _ => return Ok(false),
+                };
+
+                if bindings.len() != payload.len() {
+                    return Err(error::RuntimeError::ArgumentMismatch {
+                        expected: payload.len(),
+                        found: bindings.len(),
+                        span: self.span,
+                    });
+                }
+
+                for (binding, value) in bindings.iter().zip(payload) {
+                    if binding != "_" {
+                        self.bind_variable(binding.clone(), value.clone())?;
+                    }
+                }
+
+                Ok(true)
+            }
+        }
+    }
+
+    // Everything a name could have been meant to be: locals, globals, enums and keywords
+    fn suggest_variable(&self, name: &str) -> Option<String> {
+        let mut candidates: Vec<String> = self.variables.keys().cloned().collect();
+        if let Some(frame) = self.locals.last() {
+            candidates.extend(frame.keys().cloned());
+        }
+        candidates.extend(self.enums.keys().cloned());
+        candidates.extend(suggest::keywords());
+        if self.current_instance.is_some() {
+            candidates.push("goat".to_string());
+        }
+
+        suggest::did_you_mean(name, candidates.iter().map(String::as_str))
+    }
+
+    // Parameters first, then globals
+    pub fn lookup_variable(&self, name: &str) -> Option<&Expr> {
+        self.locals
+            .last()
+            .and_then(|frame| frame.get(name))
+            .or_else(|| self.variables.get(name))
+    }
+
+    // Nothing gets to write to a based name, not assignments, goon or vibecheck
+    fn check_not_constant(&self, name: &str) -> Result<(), error::RuntimeError> {
+        if let Some(declared_at) = self.constants.get(name) {
+            return Err(error::RuntimeError::ConstantReassign {
+                name: name.to_string(),
+                declared_at: *declared_at,
+                span: self.span,
+            });
+        }
+
+        Ok(())
+    }
+
+    // Assignment without a declaration, parameters shadow globals and everything else stays global
+    fn assign_variable(&mut self, name: String, value: Expr) -> Result<(), error::RuntimeError> {
+        self.check_not_constant(&name)?;
+
+        // Except in a generator, which keeps all of its own so two of them from one cookable don't clash
+        if self.generator_frame.is_some_and(|frame| frame + 1 == self.locals.len()) {
+            return self.bind_variable(name, value);
+        }
+
+        // A parameter gets the new value, anything else is a global
+        let name = match self.locals.last_mut() {
+            Some(frame) => match frame.entry(name) {
+                Entry::Occupied(mut local) => {
+                    local.insert(value);
+
+                    return Ok(());
+                }
+                Entry::Vacant(global) => global.into_key(),
+            },
+            None => name,
+        };
+
+        self.variables.insert(name, value);
+
+        Ok(())
+    }
+
+    // Splits a list into exactly `count` values for x, y is ...
+    fn unpack_values(&self, value: Expr, count: usize) -> Result<Vec<Expr>, error::RuntimeError> {
+        match value {
+            Expr::List(values) if values.len() == count => Ok(values),
+            Expr::List(values) => Err(error::RuntimeError::GeneralError {
+                span: self.span,
+                message: format!("Expected {} values to unpack, but found {}", count, values.len()),
+            }),
+            other => Err(error::RuntimeError::TypeError {
+                expected: "list".to_string(),
+                found: other.type_name(),
+                span: self.span,
+            }),
+        }
+    }
+
+    pub fn bind_iterators(&mut self, iterators: &[String], item: Expr) -> Result<(), error::RuntimeError> {
+        if iterators.len() == 1 {
+            self.bind_variable(iterators[0].clone(), item)?;
+        } else {
+            for (name, value) in iterators.iter().zip(self.unpack_values(item, iterators.len())?) {
+                self.bind_variable(name.clone(), value)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // New names go into the current call frame, or the globals at the top level
+    pub fn bind_variable(&mut self, name: String, value: Expr) -> Result<(), error::RuntimeError> {
+        self.check_not_constant(&name)?;
+
+        if let Some(frame) = self.locals.last_mut() {
+            frame.insert(name, value);
+        } else {
+            self.variables.insert(name, value);
+        }
+
+        Ok(())
+    }
+
     // helper
-    fn get_class_from_object(&mut self, object: &Expr) -> Result<Class, error::ParseError> {
+    fn get_class_from_object(&mut self, object: &Expr) -> Result<Class, error::RuntimeError> {
         match object {
             Expr::Ident(object_name) if object_name == "goat" => {
                 // Handle the special "goat" case
                 let current_instance = self.current_instance.clone().ok_or_else(|| {
-                    error::ParseError::GeneralError {
-                        line: self.line,
+                    error::RuntimeError::GeneralError {
+                        span: self.span,
                         message: "Cannot use 'self' outside of a method context".to_string(),
                     }
                 })?;
 
                 let instance = self.instances.get_mut(&current_instance).ok_or_else(|| {
-                    error::ParseError::GeneralError {
-                        line: self.line,
+                    error::RuntimeError::GeneralError {
+                        span: self.span,
                         message: "Current instance not found".to_string(),
                     }
                 })?;
 
                 let class_def = self.classes.get(&instance.class_name).ok_or_else(|| {
-                    error::ParseError::GeneralError {
-                        line: self.line,
+                    error::RuntimeError::GeneralError {
+                        span: self.span,
                         message: format!("Unknown class: {}", &instance.class_name),
                     }
                 })?;
@@ -563,28 +1331,27 @@ impl Interpreter {
                     variables: instance.variables.clone(),
                     class_name: instance.class_name.clone(),
                     instance_id: current_instance.clone(),
-                    is_self: true,
                 })
             }
             Expr::Ident(object_name) => {
                 // Check if the object is a variable
-                if let Some(value) = self.variables.get(object_name) {
-                    match value {
+                if let Some(value) = self.lookup_variable(object_name).cloned() {
+                    match &value {
                         Expr::Instance {
                             class_name,
                             instance_id,
                         } => {
                             let class_def = self.classes.get(class_name).ok_or_else(|| {
-                                error::ParseError::GeneralError {
-                                    line: self.line,
+                                error::RuntimeError::GeneralError {
+                                    span: self.span,
                                     message: format!("Unknown class: {}", class_name),
                                 }
                             })?;
 
                             let instance =
                                 self.instances.get_mut(instance_id).ok_or_else(|| {
-                                    error::ParseError::GeneralError {
-                                        line: self.line,
+                                    error::RuntimeError::GeneralError {
+                                        span: self.span,
                                         message: format!(
                                             "Instance not found for ID: {}",
                                             instance_id
@@ -597,24 +1364,24 @@ impl Interpreter {
                                 variables: instance.variables.clone(),
                                 class_name: class_name.clone(),
                                 instance_id: instance_id.clone(),
-                                is_self: false,
                             })
                         }
-                        _ => Err(error::ParseError::GeneralError {
-                            line: self.line,
+                        _ => Err(error::RuntimeError::GeneralError {
+                            span: self.span,
                             message: "Object calls are only supported for instance variables."
                                 .to_string(),
                         }),
                     }
                 } else {
-                    Err(error::ParseError::GeneralError {
-                        line: self.line,
-                        message: format!("Variable not found: {}", object_name),
+                    Err(error::RuntimeError::UnknownVariable {
+                        suggestion: self.suggest_variable(object_name),
+                        name: object_name.clone(),
+                        span: self.span,
                     })
                 }
             }
-            _ => Err(error::ParseError::GeneralError {
-                line: self.line,
+            _ => Err(error::RuntimeError::GeneralError {
+                span: self.span,
                 message: "Object calls of other types than IDENT are not supported".to_string(),
             }),
         }
@@ -637,13 +1404,13 @@ impl Interpreter {
     // };
     // pub fn consume_arguments<T>(
     //     args: &[Expr],
-    //     validators: &[impl Fn(&Expr) -> Result<T, error::ParseError>]
-    // ) -> Result<Vec<T>, error::ParseError> {
+    //     validators: &[impl Fn(&Expr) -> Result<T, error::RuntimeError>]
+    // ) -> Result<Vec<T>, error::RuntimeError> {
     //     if args.len() != validators.len() {
-    //         return Err(error::ParseError::ArgumentMismatch {
+    //         return Err(error::RuntimeError::ArgumentMismatch {
     //             found: args.len(),
     //             expected: validators.len(),
-    //             line: self.line,
+    //             span: self.span,
     //         });
     //     }
 
@@ -661,12 +1428,12 @@ impl Interpreter {
         args: &Vec<Expr>,
         expected: usize,
         index: usize,
-    ) -> Result<Expr, error::ParseError> {
+    ) -> Result<Expr, error::RuntimeError> {
         if index >= args.len() || args.len() != expected {
-            return Err(error::ParseError::ArgumentMismatch {
+            return Err(error::RuntimeError::ArgumentMismatch {
                 found: args.len(),
                 expected,
-                line: self.line,
+                span: self.span,
             });
         }
 
@@ -675,7 +1442,7 @@ impl Interpreter {
         Ok(arg)
     }
 
-    // pub fn execute_statements(&mut self, statements: Vec<Stmt>) -> Result<Expr, error::ParseError> {
+    // pub fn execute_statements(&mut self, statements: Vec<Stmt>) -> Result<Expr, error::RuntimeError> {
     //     for stmt in statements {
     //         match self.execute_statement(stmt)? {
     //             ControlFlow::Return(value) => {
@@ -691,40 +1458,93 @@ impl Interpreter {
     pub fn execute_user_function(
         &mut self,
         name: String,
-        _args: Vec<Expr>,
-    ) -> Result<Expr, error::ParseError> {
-        if let Some(body) = self.functions.get(&name) {
-            for stmt in body.clone() {
-                let controlflow = self.execute_statement(stmt)?;
-
-                match controlflow {
-                    ControlFlow::Return(value) => return Ok(value),
-                    _ => {}
-                }
-            }
-
-            Ok(Expr::Number(0)) // Default return value for functions
+        args: Vec<Expr>,
+    ) -> Result<Expr, error::RuntimeError> {
+        if let Some(function) = self.functions.get(&name).cloned() {
+            self.call_function(&function, args)
         } else {
-            Err(error::ParseError::UnknownFunction {
+            let candidates = self.functions.keys().map(String::as_str).chain(BUILTINS.iter().copied());
+
+            Err(error::RuntimeError::UnknownFunction {
+                suggestion: suggest::did_you_mean(&name, candidates),
                 name,
-                line: self.line,
+                span: self.span,
             })
         }
     }
 
+    // Evaluates the arguments in the callers scope, then runs the body with the params bound
+    fn call_function(
+        &mut self,
+        function: &UserFunction,
+        args: Vec<Expr>,
+    ) -> Result<Expr, error::RuntimeError> {
+        if args.len() != function.params.len() {
+            return Err(error::RuntimeError::ArgumentMismatch {
+                expected: function.params.len(),
+                found: args.len(),
+                span: self.span,
+            });
+        }
+
+        let mut frame = HashMap::new();
+        for (param, arg) in function.params.iter().zip(args) {
+            frame.insert(param.name.clone(), self.evaluate_expression(arg)?);
+        }
+
+        // Nothing runs yet, the body starts on the first resume
+        if function.is_generator {
+            return Ok(self.create_generator(function, frame));
+        }
+
+        // The call site has to be grabbed now, the body moves self.span around
+        let trace_frame = error::TraceFrame {
+            function: function.name.clone(),
+            class: function.class.clone(),
+            call_site: self.span,
+        };
+
+        self.locals.push(frame);
+        self.calls.push(trace_frame.clone());
+        let result = self.execute_body(&function.body);
+        self.calls.pop();
+        self.locals.pop();
+
+        result.map_err(|error| error.with_frame(trace_frame))
+    }
+
+    fn evaluate_arguments(&mut self, args: Vec<Expr>) -> Result<Vec<Expr>, error::RuntimeError> {
+        let mut evaluated = Vec::new();
+        for arg in args {
+            evaluated.push(self.evaluate_expression(arg)?);
+        }
+
+        Ok(evaluated)
+    }
+
+    fn execute_body(&mut self, body: &[Stmt]) -> Result<Expr, error::RuntimeError> {
+        for stmt in body {
+            if let ControlFlow::Return(value) = self.execute_statement(stmt.clone())? {
+                return Ok(value);
+            }
+        }
+
+        Ok(Expr::Number(0)) // Default return value for functions
+    }
+
     pub fn execute_function_call(
         &mut self,
         name: String,
         object: Option<Box<Expr>>,
         args: Vec<Expr>,
-    ) -> Result<Expr, error::ParseError> {
+    ) -> Result<Expr, error::RuntimeError> {
         if let Some(object) = object {
             // Firstly check if it is a lib
             let lib_name = if let Expr::Ident(object) = *object.clone() {
                 object
             } else {
-                return Err(error::ParseError::GeneralError {
-                    line: self.line,
+                return Err(error::RuntimeError::GeneralError {
+                    span: self.span,
                     message: "Object calls of other types than IDENT are not supported".to_string(),
                 });
             };
@@ -737,14 +1557,20 @@ impl Interpreter {
                 let func = lib
                     .functions
                     .get(&name)
-                    .ok_or_else(|| error::ParseError::GeneralError {
-                        line: self.line,
-                        message: format!("Unknown function: {} on object {}", name, lib_name),
+                    .ok_or_else(|| error::RuntimeError::UnknownFunction {
+                        name: format!("{}.{}", lib_name, name),
+                        suggestion: suggest::did_you_mean(&name, lib.functions.keys().map(String::as_str)),
+                        span: self.span,
                     })?;
 
                 return func(self, args);
             }
 
+            // State.GameOver(score) builds a variant with a payload
+            if let Some(enum_name) = self.enum_name_of(&object) {
+                return self.make_enum_variant(enum_name, name, args);
+            }
+
             // not a lib, it is a class
             // Use the helper function, possibly return None.
             let class = self.get_class_from_object(&*object)?;
@@ -753,29 +1579,20 @@ impl Interpreter {
                 class
                     .functions
                     .get(&name)
-                    .ok_or_else(|| error::ParseError::GeneralError {
-                        line: self.line,
-                        message: format!(
-                            "Unknown function: {} on class {}",
-                            name, class.class_name
-                        ),
+                    .ok_or_else(|| error::RuntimeError::UnknownFunction {
+                        name: format!("{}.{}", class.class_name, name),
+                        suggestion: suggest::did_you_mean(&name, class.functions.keys().map(String::as_str)),
+                        span: self.span,
                     })?;
 
-            if !class.is_self {
-                // Set active instance if its a instance call, but not a self one, then its already defined
-                self.current_instance = Some(class.instance_id.clone());
-            }
-
-            // Execute function statements
-            for stmt in func.clone() {
-                let controlflow = self.execute_statement(stmt)?;
+            let args = self.evaluate_arguments(args)?;
 
-                if let ControlFlow::Return(value) = controlflow {
-                    return Ok(value);
-                }
-            }
+            // Set active instance if its a instance call, for a self one its already the same
+            let previous_instance = self.current_instance.replace(class.instance_id.clone());
+            let result = self.call_function(func, args);
+            self.current_instance = previous_instance;
 
-            return Ok(Expr::Number(0)); // Default return value for functions
+            return result;
         }
 
         // we know for a certain that it is a normal function call
@@ -785,26 +1602,21 @@ impl Interpreter {
             "aura" => {
                 let arg = self.consume_argument(&args, 1, 0)?;
 
-                let number = match self.evaluate_expression(arg.clone())? {
-                    Expr::Number(value) => value,
-                    Expr::StringLiteral(value) => {
-                        value
-                            .parse::<i64>()
-                            .map_err(|_| error::ParseError::GeneralError {
-                                line: self.line,
-                                message: format!("Failed to parse {} as number", value),
-                            })?
-                    }
-                    _ => {
-                        return Err(error::ParseError::TypeError {
-                            expected: Expr::Number(-1),
-                            found: arg,
-                            line: self.line,
-                        })
-                    }
-                };
-
-                Ok(Expr::Number(number))
+                match self.evaluate_expression(arg)? {
+                    number @ (Expr::Number(_) | Expr::BigNumber(_)) => Ok(number),
+                    Expr::StringLiteral(value) => value
+                        .parse::<BigInt>()
+                        .map(Expr::whole_number)
+                        .map_err(|_| error::RuntimeError::GeneralError {
+                            span: self.span,
+                            message: format!("Failed to parse {} as number", value),
+                        }),
+                    found => Err(error::RuntimeError::TypeError {
+                        expected: "number".to_string(),
+                        found: found.type_name(),
+                        span: self.span,
+                    }),
+                }
             }
             "yap" => {
                 let mut output = String::new();
@@ -813,18 +1625,94 @@ impl Interpreter {
                     output.push_str(" ");
                 }
 
-                println!("{}", output);
+                let _ = writeln!(self.output, "{}", output);
 
                 Ok(Expr::Boolean(true)) // Return value for function calls
             }
+            // Next value of a generator, gimme(gen, fallback) instead of an error once it's cooked
+            "gimme" => {
+                // The generator, then an optional fallback for when it's cooked
+                if args.is_empty() || args.len() > 2 {
+                    return Err(error::RuntimeError::ArgumentMismatch {
+                        expected: args.len().clamp(1, 2),
+                        found: args.len(),
+                        span: self.span,
+                    });
+                }
+
+                let mut args = args.into_iter();
+                let generator = self.evaluate_expression(args.next().unwrap())?;
+
+                let id = match generator {
+                    Expr::Generator { id, .. } => id,
+                    other => {
+                        return Err(error::RuntimeError::GeneralError {
+                            span: self.span,
+                            message: format!("gimme expects a generator, found a {}", other.type_name()),
+                        })
+                    }
+                };
+
+                match (self.resume_generator(id)?, args.next()) {
+                    (Some(value), _) => Ok(value),
+                    (None, Some(fallback)) => self.evaluate_expression(fallback),
+                    (None, None) => Err(error::RuntimeError::GeneralError {
+                        span: self.span,
+                        message: "Generator is cooked, there is nothing left to gimme".to_string(),
+                    }),
+                }
+            }
+            // Length of lists, strings and ranges, pookies can have __thicc__
+            "thicc" => {
+                let arg = self.consume_argument(&args, 1, 0)?;
+
+                match self.evaluate_expression(arg)? {
+                    Expr::List(values) => Ok(Expr::Number(values.len() as i64)),
+                    Expr::StringLiteral(value) => Ok(Expr::Number(value.chars().count() as i64)),
+                    range @ Expr::Range { .. } => Ok(Expr::whole_number(self.range_iter(&range)?.count_left().into())),
+                    Expr::Instance {
+                        class_name,
+                        instance_id,
+                    } if self.find_method(&class_name, "__thicc__").is_some() => {
+                        self.call_method(&class_name, &instance_id, "__thicc__", Vec::new())
+                    }
+                    other => Err(error::RuntimeError::GeneralError {
+                        span: self.span,
+                        message: format!("Can't get the thiccness of a {}", other.type_name()),
+                    }),
+                }
+            }
+            // Turns a range into a real list
+            "listmaxx" => {
+                let arg = self.consume_argument(&args, 1, 0)?;
+
+                match self.evaluate_expression(arg)? {
+                    range @ Expr::Range { .. } => {
+                        Ok(Expr::List(self.range_iter(&range)?.map(Expr::Number).collect()))
+                    }
+                    Expr::List(values) => Ok(Expr::List(values)),
+                    other => Err(error::RuntimeError::TypeError {
+                        expected: "list".to_string(),
+                        found: other.type_name(),
+                        span: self.span,
+                    }),
+                }
+            }
             "attemptrizz" => {
                 // expect only one argument
 
                 let question = self.expr_to_string(self.consume_argument(&args, 1, 0)?)?;
 
-                print!("{}", question);
+                if !self.stdin {
+                    return Err(error::RuntimeError::GeneralError {
+                        span: self.span,
+                        message: "attemptrizz can't read input while an editor is debugging, use skibidyscript debug"
+                            .to_string(),
+                    });
+                }
 
-                std::io::stdout().flush().expect("Failed to flush stdout");
+                let _ = write!(self.output, "{}", question);
+                let _ = self.output.flush();
 
                 let mut input = String::new();
                 std::io::stdin()
@@ -837,3 +1725,586 @@ impl Interpreter {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::Lexer, parser::Parser};
+
+    // Runs the top level, then fein if there is one. The type checker is left out so the
+    // interpreter's own checks are what's tested
+    fn run(source: &str) -> (Interpreter, Result<(), error::RuntimeError>) {
+        let mut lexer = Lexer::new(source);
+        let (statements, parse_errors) = Parser::new(&mut lexer).unwrap().parse();
+        assert!(parse_errors.is_empty(), "{:?}", parse_errors);
+
+        let mut interpreter = Interpreter::new();
+        interpreter.output = Box::new(std::io::sink());
+
+        let result = interpreter.interpret(statements).and_then(|()| {
+            match interpreter.functions.contains_key("fein") {
+                true => interpreter.execute_user_function("fein".to_string(), vec![]).map(|_| ()),
+                false => Ok(()),
+            }
+        });
+
+        (interpreter, result)
+    }
+
+    fn number(interpreter: &Interpreter, name: &str) -> i64 {
+        match interpreter.variables.get(name) {
+            Some(Expr::Number(n)) => *n,
+            other => panic!("{} is {:?}", name, other),
+        }
+    }
+
+    // What yap would print for a global
+    fn text(interpreter: &mut Interpreter, name: &str) -> String {
+        interpreter.expr_to_string(Expr::Ident(name.to_string())).unwrap()
+    }
+
+    #[test]
+    fn constants_cant_be_reassigned() {
+        let (interpreter, result) = run("cookable fein()\n    X is 2\nslay\n\nbased X is 1\n");
+
+        assert_eq!(result.unwrap_err().code(), "E0007");
+        assert_eq!(number(&interpreter, "X"), 1);
+    }
+
+    #[test]
+    fn goon_cant_bind_a_constant() {
+        // Declared after the loop, so only the interpreter can catch it
+        let (interpreter, result) = run("\
+cookable fein()
+    goon (X in [5, 6]) eat
+    slay
+slay
+
+based X is 1
+");
+
+        assert_eq!(result.unwrap_err().code(), "E0007");
+        assert_eq!(number(&interpreter, "X"), 1);
+    }
+
+    #[test]
+    fn vibecheck_cant_bind_a_constant() {
+        let source = "\
+vibe S
+    Over(score)
+slay
+
+cookable fein()
+    vibecheck (S.Over(9)) eat
+        S.Over(SCORE) eat
+        slay
+    slay
+slay
+
+based SCORE is 1
+";
+        let (interpreter, result) = run(source);
+
+        assert_eq!(result.unwrap_err().code(), "E0007");
+        assert_eq!(number(&interpreter, "SCORE"), 1);
+    }
+
+    #[test]
+    fn vibecheck_binds_the_payload() {
+        let source = "\
+vibe S
+    Menu
+    Over(score)
+slay
+
+result is 0
+vibecheck (S.Over(9)) eat
+    S.Menu eat
+        result is 1
+    slay
+    S.Over(score) eat
+        result is score
+    slay
+slay
+";
+        let (interpreter, result) = run(source);
+
+        result.unwrap();
+        assert_eq!(number(&interpreter, "result"), 9);
+    }
+
+    #[test]
+    fn goon_unpacks_into_several_names() {
+        let (interpreter, result) = run("\
+total is 0
+goon (a, b in [[1, 2], [3, 4]]) eat
+    total is total + a * b
+slay
+");
+
+        result.unwrap();
+        assert_eq!(number(&interpreter, "total"), 14);
+    }
+
+    #[test]
+    fn multiple_return_values_unpack() {
+        let source = "cookable pair()\n    blud 1, 2\nslay\n\nx, y is cook pair()\n";
+        let (interpreter, result) = run(source);
+
+        result.unwrap();
+        assert_eq!(number(&interpreter, "x"), 1);
+        assert_eq!(number(&interpreter, "y"), 2);
+    }
+
+    #[test]
+    fn type_errors_name_the_types() {
+        let (_, result) = run("x is 1 - \"a\"\n");
+        let error = result.unwrap_err();
+
+        assert_eq!(error.code(), "E0014");
+        assert_eq!(error.message(), "expected type number, but found type number - string");
+    }
+
+    #[test]
+    fn generator_errors_keep_their_frame() {
+        let source = "\
+pookie Wave()
+    cookable __edge__()
+    slay
+
+    cookable spawn()
+        yeet 1
+        yeet 1 - \"a\"
+    slay
+slay
+
+cookable fein()
+    w is mew Wave()
+    g is cook w.spawn()
+    cook gimme(g)
+    cook gimme(g)
+slay
+";
+        let (_, result) = run(source);
+
+        match result.unwrap_err() {
+            error::RuntimeError::Traceback { error, frames } => {
+                assert_eq!(error.code(), "E0014");
+                assert_eq!(frames[0].function, "spawn");
+                assert_eq!(frames[0].class.as_deref(), Some("Wave"));
+                assert_eq!(frames[1].function, "fein");
+            }
+            other => panic!("no traceback: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn gimme_takes_a_fallback() {
+        let source = "\
+cookable one()
+    yeet 1
+slay
+
+g is cook one()
+a is cook gimme(g, 5)
+b is cook gimme(g, 5)
+";
+        let (interpreter, result) = run(source);
+
+        result.unwrap();
+        assert_eq!(number(&interpreter, "a"), 1);
+        assert_eq!(number(&interpreter, "b"), 5);
+
+        let (_, result) = run("cookable one()\n    yeet 1\nslay\n\nx is cook gimme(cook one(), 0, 0)\n");
+        assert_eq!(result.unwrap_err().message(), "expected 2 arguments, but found 3");
+    }
+
+    #[test]
+    fn numbers_past_64_bits_become_big_numbers() {
+        let (mut interpreter, result) = run("\
+max is 9223372036854775807
+sum is max + 1
+product is max * max
+back is sum - 1
+quotient is product / max
+min is -9223372036854775808
+below is min - 1
+literal is 99999999999999999999 + 1
+parsed is cook aura(\"-99999999999999999999\")
+same is sum rizz 9223372036854775808
+bigger is sum > max
+");
+
+        result.unwrap();
+        assert_eq!(text(&mut interpreter, "sum"), "9223372036854775808");
+        assert_eq!(text(&mut interpreter, "product"), "85070591730234615847396907784232501249");
+        assert_eq!(text(&mut interpreter, "below"), "-9223372036854775809");
+        assert_eq!(text(&mut interpreter, "literal"), "100000000000000000000");
+        assert_eq!(text(&mut interpreter, "parsed"), "-99999999999999999999");
+        assert_eq!(text(&mut interpreter, "same"), "sigma");
+        assert_eq!(text(&mut interpreter, "bigger"), "sigma");
+        // Back in range they're plain numbers again
+        assert_eq!(number(&interpreter, "back"), i64::MAX);
+        assert_eq!(number(&interpreter, "quotient"), i64::MAX);
+        assert_eq!(number(&interpreter, "min"), i64::MIN);
+    }
+
+    #[test]
+    fn big_numbers_dont_fit_everywhere() {
+        let (_, result) = run("goon (i in 0..99999999999999999999) eat\nslay\n");
+        let error = result.unwrap_err();
+
+        assert_eq!(error.code(), "E0018");
+        assert_eq!(error.message(), "number 99999999999999999999 is too big here");
+
+        let (_, result) = run("x is 99999999999999999999 / 0\n");
+        assert_eq!(result.unwrap_err().code(), "E0012");
+    }
+
+    #[test]
+    fn constants_can_be_read_anywhere() {
+        let (interpreter, result) = run("\
+based FPS is 60
+
+cookable fein()
+    frame is FPS * 2
+    blud frame
+slay
+");
+
+        result.unwrap();
+        assert_eq!(number(&interpreter, "FPS"), 60);
+    }
+
+    #[test]
+    fn goon_over_a_range() {
+        let (interpreter, result) = run("\
+total is 0
+goon (i in 10..=0 by -5) eat
+    total is total * 10 + i / 5
+slay
+");
+
+        result.unwrap();
+        assert_eq!(number(&interpreter, "total"), 210);
+    }
+
+    #[test]
+    fn thicc_counts_ranges_without_walking_them() {
+        let (interpreter, result) = run("\
+huge is cook thicc(0..9223372036854775807)
+odd is cook thicc(1..=9 by 2)
+");
+
+        result.unwrap();
+        assert_eq!(number(&interpreter, "huge"), i64::MAX);
+        assert_eq!(number(&interpreter, "odd"), 5);
+
+        // More numbers than an i64 holds
+        let (mut interpreter, result) = run("all is cook thicc(-9223372036854775808..=9223372036854775807)\n");
+        result.unwrap();
+        assert_eq!(text(&mut interpreter, "all"), "18446744073709551616");
+    }
+
+    const VEC2: &str = "\
+pookie Vec2()
+    cookable __edge__(x, y)
+        goat.x is x
+        goat.y is y
+    slay
+
+    cookable __plus__(other)
+        blud mew Vec2(goat.x + other.x, goat.y + other.y)
+    slay
+
+    cookable __times__(factor)
+        blud mew Vec2(goat.x * factor, goat.y * factor)
+    slay
+
+    cookable __rizz__(other)
+        blud goat.x rizz other.x and goat.y rizz other.y
+    slay
+
+    cookable __lessthan__(other)
+        blud goat.x + goat.y < other.x + other.y
+    slay
+slay
+";
+
+    #[test]
+    fn operators_call_magic_methods() {
+        let source = format!(
+            "{}
+a is mew Vec2(1, 2)
+b is mew Vec2(3, 4)
+c is a + b * 2
+x is c.x
+y is c.y
+same is c rizz mew Vec2(7, 10)
+smaller is a < b
+bigger is a > b
+",
+            VEC2
+        );
+        let (mut interpreter, result) = run(&source);
+
+        result.unwrap();
+        assert_eq!(number(&interpreter, "x"), 7);
+        assert_eq!(number(&interpreter, "y"), 10);
+        assert_eq!(text(&mut interpreter, "same"), "sigma");
+        assert_eq!(text(&mut interpreter, "smaller"), "sigma");
+        assert_eq!(text(&mut interpreter, "bigger"), "ohio");
+    }
+
+    #[test]
+    fn operators_without_a_magic_method_are_type_errors() {
+        let (_, result) = run(&format!("{}\nv is mew Vec2(1, 2) - mew Vec2(1, 2)\n", VEC2));
+
+        assert_eq!(result.unwrap_err().code(), "E0014");
+    }
+
+    #[test]
+    fn ordering_needs_both_sides_to_answer() {
+        let source = "\
+pookie Score()
+    cookable __edge__(points)
+        goat.points is points
+    slay
+
+    cookable __lessthan__(other)
+        blud goat.points < other
+    slay
+slay
+
+score is mew Score(10)
+smaller is score < 50
+";
+        let (mut interpreter, result) = run(source);
+
+        result.unwrap();
+        assert_eq!(text(&mut interpreter, "smaller"), "sigma");
+
+        // 5 can't say whether it's less than a Score, so there's no telling if they're equal
+        let (_, result) = run(&format!("{}bigger is score > 5\n", source));
+        let error = result.unwrap_err();
+
+        assert_eq!(error.code(), "E0014");
+        assert_eq!(error.message(), "expected type bool, but found type Score > number");
+    }
+
+    #[test]
+    fn yap_prints_nested_values() {
+        let source = "\
+pookie Node()
+    cookable __edge__(name)
+        goat.name is name
+        goat.next is ohio
+    slay
+slay
+
+vibe S
+    Over(score)
+slay
+
+list is [1, \"two\", [sigma, ohio]]
+node is mew Node(\"a\")
+node.next is node
+over is S.Over(\"high\")
+range is 0..=10 by 2
+";
+        let (mut interpreter, result) = run(source);
+
+        result.unwrap();
+        assert_eq!(text(&mut interpreter, "list"), "[1, \"two\", [sigma, ohio]]");
+        assert_eq!(text(&mut interpreter, "node"), "Node{name: \"a\", next: Node{...}}");
+        assert_eq!(text(&mut interpreter, "over"), "S.Over(\"high\")");
+        assert_eq!(text(&mut interpreter, "range"), "0..=10 by 2");
+    }
+
+    #[test]
+    fn rizz_compares_any_two_values() {
+        let mut interpreter = Interpreter::new();
+        let list = |items: &[i64]| Expr::List(items.iter().map(|n| Expr::Number(*n)).collect());
+
+        assert!(interpreter.values_equal(&list(&[1, 2]), &list(&[1, 2])).unwrap());
+        assert!(!interpreter.values_equal(&list(&[1, 2]), &list(&[1, 2, 3])).unwrap());
+        assert!(!interpreter.values_equal(&Expr::Number(1), &Expr::StringLiteral("1".to_string())).unwrap());
+        assert!(!interpreter.values_equal(&Expr::Boolean(true), &Expr::Number(1)).unwrap());
+    }
+
+    #[test]
+    fn strings_and_lists_are_ordered() {
+        let (mut interpreter, result) = run("\
+pookie P()
+    cookable __edge__()
+    slay
+slay
+
+words is \"apple\" < \"banana\"
+lists is [1, 2, 3] < [1, 3]
+prefix is [1, 2] < [1, 2, 0]
+p is mew P()
+same is p rizz p
+other is p rizz mew P()
+");
+
+        result.unwrap();
+        for name in ["words", "lists", "prefix", "same"] {
+            assert_eq!(text(&mut interpreter, name), "sigma", "{}", name);
+        }
+        assert_eq!(text(&mut interpreter, "other"), "ohio");
+    }
+
+    #[test]
+    fn unorderable_values_are_type_errors() {
+        let (_, result) = run("x is 1 < \"2\"\n");
+
+        assert_eq!(result.unwrap_err().code(), "E0014");
+    }
+
+    #[test]
+    fn what_counts_as_true() {
+        let mut interpreter = Interpreter::new();
+        let falsy = [
+            Expr::Boolean(false),
+            Expr::Number(0),
+            Expr::StringLiteral(String::new()),
+            Expr::List(Vec::new()),
+            Expr::Range {
+                start: Box::new(Expr::Number(0)),
+                end: Box::new(Expr::Number(0)),
+                step: None,
+                inclusive: false,
+            },
+        ];
+
+        for value in falsy {
+            assert!(!interpreter.is_truthy(&value).unwrap(), "{:?}", value);
+        }
+
+        let truthy = [Expr::Number(-1), Expr::StringLiteral("0".to_string()), Expr::List(vec![Expr::Number(0)])];
+        for value in truthy {
+            assert!(interpreter.is_truthy(&value).unwrap(), "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn and_or_stop_early() {
+        // The cook on the right would fail, so it can't have run
+        let (mut interpreter, result) = run("\
+a is ohio and cook missing()
+b is 1 or cook missing()
+c is not []
+");
+
+        result.unwrap();
+        assert_eq!(text(&mut interpreter, "a"), "ohio");
+        assert_eq!(text(&mut interpreter, "b"), "sigma");
+        assert_eq!(text(&mut interpreter, "c"), "sigma");
+    }
+
+    #[test]
+    fn strict_conditions_have_to_be_bools() {
+        let mut interpreter = Interpreter::new();
+        interpreter.strict = true;
+
+        assert!(interpreter.is_truthy(&Expr::Boolean(true)).unwrap());
+        assert_eq!(interpreter.is_truthy(&Expr::Number(1)).unwrap_err().code(), "E0014");
+    }
+
+    #[test]
+    fn errors_carry_the_cookables_they_left() {
+        let (_, result) = run("\
+cookable inner()
+    blud 1 / 0
+slay
+
+cookable outer()
+    blud cook inner()
+slay
+
+x is cook outer()
+");
+
+        let error::RuntimeError::Traceback { error, frames } = result.unwrap_err() else {
+            panic!("no traceback");
+        };
+
+        assert_eq!(error.code(), "E0012");
+        assert_eq!(error.span().map(|span| span.line), Some(2));
+
+        let frames: Vec<String> = frames.iter().map(|frame| frame.to_string()).collect();
+        assert_eq!(frames, vec!["in inner, cooked on line 6, column 15", "in outer, cooked on line 9, column 11"]);
+    }
+
+    #[test]
+    fn unknown_names_come_with_a_suggestion() {
+        let (_, result) = run("cookable fein()\n    score is 1\n    cook yap(scroe)\nslay\n");
+        assert!(matches!(
+            result.unwrap_err(),
+            error::RuntimeError::Traceback { error, .. }
+                if matches!(&*error, error::RuntimeError::UnknownVariable { suggestion: Some(s), .. } if s == "score")
+        ));
+
+        let (_, result) = run("x is cook yapp(1)\n");
+        assert!(matches!(
+            result.unwrap_err(),
+            error::RuntimeError::UnknownFunction { suggestion: Some(s), .. } if s == "yap"
+        ));
+    }
+
+    #[test]
+    fn generators_pick_up_where_they_left_off() {
+        let (interpreter, result) = run("\
+cookable pairs(n)
+    i is 0
+    skibidi (i < n) eat
+        goon (j in 0..2) eat
+            sus (j rizz 1) eat
+                ghost
+            slay
+            yeet i * 10 + j
+        slay
+        i is i + 1
+    slay
+    yeet 9
+slay
+
+seen is 0
+goon (value in cook pairs(3)) eat
+    seen is seen * 100 + value
+slay
+");
+
+        result.unwrap();
+        // 0, 10, 20, then the 9 after the loop
+        assert_eq!(number(&interpreter, "seen"), 102009);
+    }
+
+    #[test]
+    fn generators_from_one_cookable_keep_their_own_variables() {
+        let (interpreter, result) = run("\
+cookable count(start)
+    i is start
+    skibidi (sigma) eat
+        yeet i
+        i is i + 1
+    slay
+slay
+
+a is cook count(0)
+b is cook count(100)
+first is cook gimme(a)
+second is cook gimme(b)
+third is cook gimme(a)
+fourth is cook gimme(b)
+");
+
+        result.unwrap();
+        assert_eq!(number(&interpreter, "first"), 0);
+        assert_eq!(number(&interpreter, "second"), 100);
+        assert_eq!(number(&interpreter, "third"), 1);
+        assert_eq!(number(&interpreter, "fourth"), 101);
+        assert!(!interpreter.variables.contains_key("i"));
+    }
+}
@@ -1,9 +1,12 @@
 // lexer.rs
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt};
+
+use num_bigint::BigInt;
+use serde::Serialize;
 
 use crate::error;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum Token {
     // Core
     Cookable,
@@ -18,11 +21,18 @@ pub enum Token {
     Slay,
     Rizz,
     Blud,
+    Yeet,
     Ghost,
     Cap,
     Gyatt,
     Goon,
     In,
+    Based,
+    Vibe,
+    VibeCheck,
+    And,
+    Or,
+    Not,
 
     // Classes
     Pookie,
@@ -31,6 +41,8 @@ pub enum Token {
     // General
     Ident(String),
     Number(i64),
+    // Past what an i64 holds
+    BigNumber(#[serde(serialize_with = "serialize_big")] BigInt),
     StringLiteral(String),
     
     // Args
@@ -39,6 +51,8 @@ pub enum Token {
     LeftBracket,
     RightBracket,
     Comma,
+    Colon,
+    Arrow,
     // Operators
     Plus,
     Minus,
@@ -47,14 +61,115 @@ pub enum Token {
     GreaterThan,
     LessThan,
     Dot,
+    DotDot,
+    DotDotEq,
     EOF,
 }
 
+// Big numbers go into JSON as a string of digits, a JSON number that long would get rounded
+pub fn serialize_big<S: serde::Serializer>(value: &BigInt, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+// Where something is in the source. Lines and columns start at 1, the end is just past the last character
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl Span {
+    // From the start of this span to the end of another one
+    pub fn to(self, other: Span) -> Span {
+        Span {
+            end_line: other.end_line,
+            end_column: other.end_column,
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+// A -- comment, which the parser never sees. Kept on the side for the formatter
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    pub text: String, // Everything after the --
+    pub span: Span,
+    pub own_line: bool, // Nothing but whitespace before it on its line
+}
+
+// How the token is written in a script, for error messages
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Token::Cookable => "cookable",
+            Token::Cook => "cook",
+            Token::Is => "is",
+            Token::Skibidi => "skibidi",
+            Token::Sigma => "sigma",
+            Token::NewLine => "new line",
+            Token::Ohio => "ohio",
+            Token::Suspect => "sus",
+            Token::Eat => "eat",
+            Token::Slay => "slay",
+            Token::Rizz => "rizz",
+            Token::Blud => "blud",
+            Token::Yeet => "yeet",
+            Token::Ghost => "ghost",
+            Token::Cap => "cap",
+            Token::Gyatt => "gyatt",
+            Token::Goon => "goon",
+            Token::In => "in",
+            Token::Based => "based",
+            Token::Vibe => "vibe",
+            Token::VibeCheck => "vibecheck",
+            Token::And => "and",
+            Token::Or => "or",
+            Token::Not => "not",
+            Token::Pookie => "pookie",
+            Token::Mew => "mew",
+            Token::Ident(name) => name,
+            Token::Number(value) => return write!(f, "{}", value),
+            Token::BigNumber(value) => return write!(f, "{}", value),
+            Token::StringLiteral(value) => return write!(f, "\"{}\"", value),
+            Token::LeftParen => "(",
+            Token::RightParen => ")",
+            Token::LeftBracket => "[",
+            Token::RightBracket => "]",
+            Token::Comma => ",",
+            Token::Colon => ":",
+            Token::Arrow => "->",
+            Token::Plus => "+",
+            Token::Minus => "-",
+            Token::Star => "*",
+            Token::Slash => "/",
+            Token::GreaterThan => ">",
+            Token::LessThan => "<",
+            Token::Dot => ".",
+            Token::DotDot => "..",
+            Token::DotDotEq => "..=",
+            Token::EOF => "end of file",
+        };
+
+        write!(f, "{}", text)
+    }
+}
+
 pub struct Lexer {
     input: Vec<char>,
     position: usize,
     keywords: HashMap<String, Token>,
-    pub line: usize, // Track the current line number
+    pub line: usize,   // Track the current line number
+    pub column: usize, // and the column on it
+    token_start: (usize, usize),
+    pub comments: Vec<Comment>, // Every comment skipped so far, in order
 }
 
 impl Lexer {
@@ -71,11 +186,18 @@ impl Lexer {
         keywords.insert("slay".into(), Token::Slay);
         keywords.insert("rizz".into(), Token::Rizz);
         keywords.insert("blud".into(), Token::Blud);
+        keywords.insert("yeet".into(), Token::Yeet);
         keywords.insert("ghost".into(), Token::Ghost);
         keywords.insert("cap".into(), Token::Cap);
         keywords.insert("gyatt".into(), Token::Gyatt);
         keywords.insert("goon".into(), Token::Goon);
         keywords.insert("in".into(), Token::In);
+        keywords.insert("based".into(), Token::Based);
+        keywords.insert("vibe".into(), Token::Vibe);
+        keywords.insert("vibecheck".into(), Token::VibeCheck);
+        keywords.insert("and".into(), Token::And);
+        keywords.insert("or".into(), Token::Or);
+        keywords.insert("not".into(), Token::Not);
 
         keywords.insert("pookie".into(), Token::Pookie);
         keywords.insert("mew".into(), Token::Mew);
@@ -86,13 +208,30 @@ impl Lexer {
             position: 0,
             keywords,
             line: 1,
+            column: 1,
+            token_start: (1, 1),
+            comments: Vec::new(),
         }
     }
 
+    pub fn keyword_names(&self) -> Vec<String> {
+        self.keywords.keys().cloned().collect()
+    }
+
+    // Everything goes through here, so this is the only place that has to count lines
     fn next_char(&mut self) -> Option<char> {
         if self.position < self.input.len() {
             self.position += 1;
-            Some(self.input[self.position - 1])
+            let ch = self.input[self.position - 1];
+
+            if ch == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+
+            Some(ch)
         } else {
             None
         }
@@ -117,9 +256,26 @@ impl Lexer {
         }
     }
 
-    pub fn next_token(&mut self) -> Result<Token, error::ParseError> {
+    pub fn next_token(&mut self) -> Result<(Token, Span), error::ParseError> {
         self.skip_whitespace();
 
+        self.token_start = (self.line, self.column);
+        let token = self.read_token()?;
+
+        Ok((token, self.token_span()))
+    }
+
+    // The token read so far, used for errors too
+    fn token_span(&self) -> Span {
+        Span {
+            line: self.token_start.0,
+            column: self.token_start.1,
+            end_line: self.line,
+            end_column: self.column,
+        }
+    }
+
+    fn read_token(&mut self) -> Result<Token, error::ParseError> {
         match self.next_char() {
             Some('"') => Ok(self.read_string()),
             Some('(') => Ok(Token::LeftParen),
@@ -127,11 +283,33 @@ impl Lexer {
             Some('[') => Ok(Token::LeftBracket),
             Some(']') => Ok(Token::RightBracket),
             Some(',') => Ok(Token::Comma),
-            Some('.') => Ok(Token::Dot),
+            Some(':') => Ok(Token::Colon),
+            Some('.') => {
+                if self.peek_char() != Some('.') {
+                    return Ok(Token::Dot);
+                }
+
+                self.next_char();
+
+                // 0..=10 includes the end
+                if self.peek_char() == Some('=') {
+                    self.next_char();
+                    Ok(Token::DotDotEq)
+                } else {
+                    Ok(Token::DotDot)
+                }
+            }
             Some('\n') => Ok(Token::NewLine),
             // meth operators
             Some('+') => Ok(Token::Plus),
-            Some('-') => Ok(Token::Minus),
+            Some('-') => {
+                if self.peek_char() == Some('>') {
+                    self.next_char();
+                    Ok(Token::Arrow)
+                } else {
+                    Ok(Token::Minus)
+                }
+            }
             Some('*') => Ok(Token::Star),
             Some('/') => Ok(Token::Slash),
             Some('>') => Ok(Token::GreaterThan),
@@ -142,7 +320,7 @@ impl Lexer {
             Some(ch) => match ch {
                 _ => Err(error::ParseError::LexerUnexpectedChar {
                     found: ch.to_string(),
-                    line: self.line,
+                    span: self.token_span(),
                 }),
             },
         }
@@ -151,17 +329,12 @@ impl Lexer {
     fn skip_whitespace(&mut self) {
         while let Some(ch) = self.peek_char() {
             if ch.is_whitespace() {
-                if ch == '\n' {
-                    self.line += 1; // Increment the line count for newlines
-                }
                 self.next_char(); // Skip whitespace characters
             } else if ch == '-' {
                 // Peek at the next character
                 if let Some(next_ch) = self.peek_next_char() {
                     if next_ch == '-' {
-                        self.next_char(); // Skip the '-' character
-                        self.next_char(); // Skip the next '-' character
-                        self.skip_comment(); // Skip the rest of the comment
+                        self.skip_comment();
                     } else {
                         // It's a minus sign, not the start of a comment
                         break;
@@ -176,13 +349,39 @@ impl Lexer {
         }
     }
 
+    // Reads a comment up to the end of its line and keeps it as trivia
     fn skip_comment(&mut self) {
-        while let Some(ch) = self.next_char() {
+        let start = (self.line, self.column);
+        let own_line = self.input[..self.position]
+            .iter()
+            .rev()
+            .take_while(|ch| **ch != '\n')
+            .all(|ch| ch.is_whitespace());
+
+        // The two dashes
+        self.next_char();
+        self.next_char();
+
+        let mut text = String::new();
+        while let Some(ch) = self.peek_char() {
             if ch == '\n' {
-                self.line += 1;
                 break;
             }
+
+            text.push(ch);
+            self.next_char();
         }
+
+        self.comments.push(Comment {
+            text: text.trim_end().to_string(),
+            span: Span {
+                line: start.0,
+                column: start.1,
+                end_line: self.line,
+                end_column: self.column,
+            },
+            own_line,
+        });
     }
 
     fn read_string(&mut self) -> Token {
@@ -226,6 +425,83 @@ impl Lexer {
                 break;
             }
         }
-        Token::Number(number.parse().unwrap())
+        // Anything past an i64 is a big number, digits only can't fail to parse as one
+        match number.parse() {
+            Ok(value) => Token::Number(value),
+            Err(_) => Token::BigNumber(number.parse().unwrap()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(source: &str) -> Vec<(Token, Span)> {
+        let mut lexer = Lexer::new(source);
+        let mut tokens = Vec::new();
+
+        loop {
+            let (token, span) = lexer.next_token().unwrap();
+            if token == Token::EOF {
+                return tokens;
+            }
+
+            tokens.push((token, span));
+        }
+    }
+
+    fn span(line: usize, column: usize, end_line: usize, end_column: usize) -> Span {
+        Span {
+            line,
+            column,
+            end_line,
+            end_column,
+        }
+    }
+
+    #[test]
+    fn every_token_has_a_span() {
+        let tokens = tokens("x is 12\n  cook yap(\"hi\") -- done\n");
+
+        assert_eq!(
+            tokens,
+            vec![
+                (Token::Ident("x".to_string()), span(1, 1, 1, 2)),
+                (Token::Is, span(1, 3, 1, 5)),
+                (Token::Number(12), span(1, 6, 1, 8)),
+                (Token::Cook, span(2, 3, 2, 7)),
+                (Token::Ident("yap".to_string()), span(2, 8, 2, 11)),
+                (Token::LeftParen, span(2, 11, 2, 12)),
+                (Token::StringLiteral("hi".to_string()), span(2, 12, 2, 16)),
+                (Token::RightParen, span(2, 16, 2, 17)),
+            ]
+        );
+    }
+
+    #[test]
+    fn strings_can_span_lines() {
+        let tokens = tokens("\"a\nb\" x");
+
+        assert_eq!(tokens[0].1, span(1, 1, 2, 3));
+        assert_eq!(tokens[1].1, span(2, 4, 2, 5));
+    }
+
+    #[test]
+    fn columns_count_characters_not_bytes() {
+        let tokens = tokens("\"💀💀\" x");
+
+        assert_eq!(tokens[1].1, span(1, 6, 1, 7));
+    }
+
+    #[test]
+    fn huge_numbers_are_big_numbers() {
+        let tokens = tokens("x is 99999999999999999999 9223372036854775807");
+
+        assert_eq!(
+            tokens[2],
+            (Token::BigNumber("99999999999999999999".parse().unwrap()), span(1, 6, 1, 26))
+        );
+        assert_eq!(tokens[3].0, Token::Number(i64::MAX));
     }
 }
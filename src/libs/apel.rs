@@ -3,9 +3,13 @@ use std::io::{self, Write};
 use std::thread::sleep;
 use std::time::Duration;
 
-use crate::{error, interpreter::Interpreter, parser::Expr};
+use crate::{
+    error,
+    interpreter::Interpreter,
+    parser::{Expr, Type},
+};
 
-use super::{LibFunctions, LibState, Library};
+use super::{LibFunctions, LibSignatures, LibState, Library, Signature};
 
 // constant of library name
 
@@ -13,21 +17,28 @@ pub const LIBRARY_NAME: &str = "apel";
 
 pub fn load_apel_library() -> Library {
     let mut functions: LibFunctions = HashMap::new();
+    let mut signatures: LibSignatures = HashMap::new();
+
     functions.insert("spawnTheApel".to_string(), spawn_apel_builtin);
+    signatures.insert(
+        "spawnTheApel".to_string(),
+        Signature::new(vec![Type::Number], Type::Any),
+    );
 
     // Add more functions as needed
     Library {
         functions,
+        signatures,
         state: LibState::None,
     }
 }
 
-fn spawn_apel_builtin(itp: &mut Interpreter, args: Vec<Expr>) -> Result<Expr, error::ParseError> {
+fn spawn_apel_builtin(itp: &mut Interpreter, args: Vec<Expr>) -> Result<Expr, error::RuntimeError> {
     let secretnum = itp.expr_to_number(itp.consume_argument(&args, 1, 0)?)?;
 
     if secretnum != 420 {
-        return Err(error::ParseError::GeneralError {
-            line: itp.line,
+        return Err(error::RuntimeError::GeneralError {
+            span: itp.span,
             message: "arg must be secret number".to_string(),
         });
     }
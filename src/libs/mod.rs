@@ -1,6 +1,10 @@
 use std::collections::HashMap;
 
-use crate::{error, interpreter::Interpreter, parser::Expr};
+use crate::{
+    error,
+    interpreter::Interpreter,
+    parser::{Expr, Type},
+};
 
 pub mod nerd;
 pub mod skui;
@@ -14,14 +18,42 @@ pub enum LibState {
 
 pub struct Library {
     pub functions: HashMap<String, BuiltinFunction>,
+    pub signatures: LibSignatures,
     pub state: LibState,
 }
 
 pub type BuiltinFunction =
-    fn(&mut Interpreter, Vec<Expr>) -> Result<Expr, error::ParseError>;
+    fn(&mut Interpreter, Vec<Expr>) -> Result<Expr, error::RuntimeError>;
 
 pub type LibFunctions = HashMap<String, BuiltinFunction>;
 
+// What the typecheck pass knows about a builtin
+#[derive(Debug, Clone)]
+pub struct Signature {
+    pub params: Vec<Type>,
+    pub returns: Type,
+}
+
+impl Signature {
+    pub fn new(params: Vec<Type>, returns: Type) -> Self {
+        Signature { params, returns }
+    }
+}
+
+pub type LibSignatures = HashMap<String, Signature>;
+
+// Everything gyatt can import
+pub const LIBRARY_NAMES: &[&str] = &[nerd::LIBRARY_NAME, skui::LIBRARY_NAME, apel::LIBRARY_NAME];
+
+pub fn load_library(lib_name: &str) -> Option<Library> {
+    match lib_name {
+        nerd::LIBRARY_NAME => Some(nerd::load_nerd_library()),
+        skui::LIBRARY_NAME => Some(skui::load_skui_library()),
+        apel::LIBRARY_NAME => Some(apel::load_apel_library()),
+        _ => None,
+    }
+}
+
 pub fn get_lib_state<'a>(itp: &'a mut Interpreter, lib_name: &str) -> &'a mut LibState {
     let lib = itp.libs.get_mut(lib_name).unwrap();
     &mut lib.state
@@ -1,8 +1,12 @@
 use std::collections::HashMap;
 
-use crate::{error, interpreter::Interpreter, parser::Expr};
+use crate::{
+    error,
+    interpreter::Interpreter,
+    parser::{Expr, Type},
+};
 
-use super::{LibFunctions, LibState, Library};
+use super::{LibFunctions, LibSignatures, LibState, Library, Signature};
 
 // constant of library name
 
@@ -10,20 +14,30 @@ pub const LIBRARY_NAME: &str = "nerd";
 
 pub fn load_nerd_library() -> Library {
     let mut functions: LibFunctions = HashMap::new();
+    let mut signatures: LibSignatures = HashMap::new();
+
     functions.insert("randInt".to_string(), rand_int_builtin);
+    signatures.insert(
+        "randInt".to_string(),
+        Signature::new(vec![Type::Number, Type::Number], Type::Number),
+    );
 
     // Add more functions as needed
 
-    Library { functions, state: LibState::None }
+    Library {
+        functions,
+        signatures,
+        state: LibState::None,
+    }
 }
 
-fn rand_int_builtin(itp: &mut Interpreter, args: Vec<Expr>) -> Result<Expr, error::ParseError> {
+fn rand_int_builtin(itp: &mut Interpreter, args: Vec<Expr>) -> Result<Expr, error::RuntimeError> {
     let min = itp.expr_to_number(itp.consume_argument(&args, 2, 0)?)?;
     let max = itp.expr_to_number(itp.consume_argument(&args, 2, 1)?)?;
 
     // let states = let LibState::NerdState(state) = state else {
-    //     return Err(error::ParseError::GeneralError {
-    //         line: itp.line,
+    //     return Err(error::RuntimeError::GeneralError {
+    //         span: itp.span,
     //         message: "Invalid state".to_string(),
     //     });
     // };
@@ -31,8 +45,8 @@ fn rand_int_builtin(itp: &mut Interpreter, args: Vec<Expr>) -> Result<Expr, erro
     // state.
 
     if min > max {
-        return Err(error::ParseError::GeneralError {
-            line: itp.line,
+        return Err(error::RuntimeError::GeneralError {
+            span: itp.span,
             message: "min cannot be greater than max".to_string(),
         });
     }
@@ -20,7 +20,7 @@ impl Default for Clock {
     }
 }
 
-pub fn clock_set_fps_builtin(itp: &mut Interpreter, args: Vec<Expr>) -> Result<Expr, error::ParseError> {
+pub fn clock_set_fps_builtin(itp: &mut Interpreter, args: Vec<Expr>) -> Result<Expr, error::RuntimeError> {
     let fps = itp.expr_to_number(itp.consume_argument(&args, 1, 0)?)? as u32;
 
     let state = super::load_skui_state(itp)?;
@@ -36,13 +36,14 @@ pub fn clock_set_fps_builtin(itp: &mut Interpreter, args: Vec<Expr>) -> Result<E
     Ok(Expr::Boolean(true))
 }
 
-pub fn clock_tick_builtin(itp: &mut Interpreter, _args: Vec<Expr>) -> Result<Expr, error::ParseError> {
+pub fn clock_tick_builtin(itp: &mut Interpreter, _args: Vec<Expr>) -> Result<Expr, error::RuntimeError> {
     let state = super::load_skui_state(itp)?;
 
     let clock = if let Some(clock) = &mut state.clock {
         clock
     } else {
         println!("Clock not initialized");
+        super::coroutine::tick_coroutines(itp)?;
         return Ok(Expr::Boolean(true));
     };
 
@@ -68,5 +69,8 @@ pub fn clock_tick_builtin(itp: &mut Interpreter, _args: Vec<Expr>) -> Result<Exp
     // Return the frame time in milliseconds (like Pygame's clock.tick() return value)
     let frame_time_ms = frame_time.as_millis() as i64;  // Convert to milliseconds
 
+    // New frame, let the coroutines that are done waiting run
+    super::coroutine::tick_coroutines(itp)?;
+
     Ok(Expr::Number(frame_time_ms))
 }
@@ -0,0 +1,176 @@
+use std::time::{Duration, Instant};
+
+use crate::error;
+use crate::interpreter::Interpreter;
+use crate::parser::{Expr, WaitFor};
+
+// A generator that skui resumes once per frame. Whatever it yeets decides when it runs next,
+// skui.waitFrames(n) or skui.waitMs(n), anything else means the next frame.
+#[derive(Debug)]
+pub struct Coroutine {
+    generator: usize,
+    wake: Wake,
+}
+
+#[derive(Debug)]
+enum Wake {
+    NextFrame,
+    Frames(i64),
+    At(Instant),
+}
+
+impl Coroutine {
+    // Counts down frame waits, so only call it once per tick
+    fn ready(&mut self, now: Instant) -> bool {
+        match self.wake {
+            Wake::NextFrame => true,
+            Wake::Frames(remaining) if remaining <= 1 => true,
+            Wake::Frames(remaining) => {
+                self.wake = Wake::Frames(remaining - 1);
+                false
+            }
+            Wake::At(time) => now >= time,
+        }
+    }
+}
+
+pub fn start_coroutine_builtin(itp: &mut Interpreter, args: Vec<Expr>) -> Result<Expr, error::RuntimeError> {
+    let generator = itp.consume_argument(&args, 1, 0)?;
+
+    let id = match itp.evaluate_expression(generator)? {
+        Expr::Generator { id, .. } => id,
+        other => {
+            return Err(error::RuntimeError::GeneralError {
+                span: itp.span,
+                message: format!("startCoroutine expects a generator, found a {}", other.type_name()),
+            })
+        }
+    };
+
+    let state = super::load_skui_state(itp)?;
+
+    // It gets its first run on the next tick
+    state.coroutines.push(Coroutine {
+        generator: id,
+        wake: Wake::NextFrame,
+    });
+
+    Ok(Expr::Boolean(true))
+}
+
+pub fn wait_frames_builtin(itp: &mut Interpreter, args: Vec<Expr>) -> Result<Expr, error::RuntimeError> {
+    let frames = itp.expr_to_number(itp.consume_argument(&args, 1, 0)?)?;
+
+    Ok(Expr::Wait(WaitFor::Frames(frames)))
+}
+
+pub fn wait_ms_builtin(itp: &mut Interpreter, args: Vec<Expr>) -> Result<Expr, error::RuntimeError> {
+    let millis = itp.expr_to_number(itp.consume_argument(&args, 1, 0)?)?;
+
+    Ok(Expr::Wait(WaitFor::Millis(millis)))
+}
+
+pub fn coroutines_running_builtin(itp: &mut Interpreter, _args: Vec<Expr>) -> Result<Expr, error::RuntimeError> {
+    let state = super::load_skui_state(itp)?;
+
+    Ok(Expr::Number(state.coroutines.len() as i64))
+}
+
+pub fn tick_coroutines_builtin(itp: &mut Interpreter, _args: Vec<Expr>) -> Result<Expr, error::RuntimeError> {
+    tick_coroutines(itp)?;
+
+    Ok(Expr::Boolean(true))
+}
+
+// Resumes every coroutine that is done waiting, clockEdge calls this once per frame
+pub fn tick_coroutines(itp: &mut Interpreter) -> Result<(), error::RuntimeError> {
+    let state = super::load_skui_state(itp)?;
+
+    // Taken out while they run, coroutines can start other coroutines
+    let mut coroutines = std::mem::take(&mut state.coroutines).into_iter();
+    let now = Instant::now();
+
+    let mut still_running = Vec::new();
+
+    while let Some(mut coroutine) = coroutines.next() {
+        if !coroutine.ready(now) {
+            still_running.push(coroutine);
+            continue;
+        }
+
+        let yielded = match itp.resume_generator(coroutine.generator) {
+            Ok(yielded) => yielded,
+            Err(e) => {
+                // Only the one that failed is dropped, the rest keep going next frame
+                still_running.extend(coroutines);
+                put_back(itp, still_running)?;
+
+                return Err(e);
+            }
+        };
+
+        coroutine.wake = match yielded {
+            Some(Expr::Wait(WaitFor::Frames(frames))) => Wake::Frames(frames),
+            Some(Expr::Wait(WaitFor::Millis(millis))) => {
+                Wake::At(now + Duration::from_millis(millis.max(0) as u64))
+            }
+            Some(_) => Wake::NextFrame,
+            None => continue, // Cooked, drop it
+        };
+
+        still_running.push(coroutine);
+    }
+
+    put_back(itp, still_running)
+}
+
+// Ahead of any coroutines that were started while these ran
+fn put_back(itp: &mut Interpreter, mut still_running: Vec<Coroutine>) -> Result<(), error::RuntimeError> {
+    let state = super::load_skui_state(itp)?;
+    still_running.append(&mut state.coroutines);
+    state.coroutines = still_running;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::Lexer, parser::Parser};
+
+    #[test]
+    fn a_failing_coroutine_leaves_the_rest_running() {
+        let source = "\
+gyatt skui
+
+cookable fine()
+    skibidi (sigma) eat
+        yeet 1
+    slay
+slay
+
+cookable broken()
+    yeet 1 - \"a\"
+slay
+
+cookable fein()
+    cook skui.startCoroutine(cook fine())
+    cook skui.startCoroutine(cook broken())
+    cook skui.startCoroutine(cook fine())
+    cook skui.tickCoroutines()
+slay
+";
+        let mut lexer = Lexer::new(source);
+        let (statements, errors) = Parser::new(&mut lexer).unwrap().parse();
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        let mut itp = Interpreter::new();
+        itp.output = Box::new(std::io::sink());
+        itp.interpret(statements).unwrap();
+
+        let result = itp.execute_user_function("fein".to_string(), vec![]);
+
+        assert_eq!(result.unwrap_err().code(), "E0014");
+        assert_eq!(super::super::load_skui_state(&mut itp).unwrap().coroutines.len(), 2);
+    }
+}
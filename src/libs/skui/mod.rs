@@ -1,5 +1,5 @@
-use super::{get_lib_state, LibFunctions, LibState, Library};
-use crate::{error, interpreter::Interpreter};
+use super::{get_lib_state, LibFunctions, LibSignatures, LibState, Library, Signature};
+use crate::{error, interpreter::Interpreter, parser::Type};
 
 use std::collections::HashMap;
 
@@ -10,6 +10,7 @@ use winit::event_loop::{ActiveEventLoop, EventLoop};
 use winit::window::{Window, WindowId};
 
 pub mod clock;
+pub mod coroutine;
 pub mod window;
 pub mod utils;
 
@@ -36,48 +37,107 @@ pub struct SkuiState {
     app: Option<SkuiApp>,
     event_loop: Option<EventLoop<()>>,
     clock: Option<clock::Clock>,
+    coroutines: Vec<coroutine::Coroutine>,
 }
 
 // https://www.reddit.com/r/rust/comments/1dnaase/rust_and_winit_0303/
 pub fn load_skui_library() -> Library {
     let mut functions: LibFunctions = HashMap::new();
+    let mut signatures: LibSignatures = HashMap::new();
+
     functions.insert("createWindow".to_string(), window::create_window_builtin);
+    signatures.insert(
+        "createWindow".to_string(),
+        Signature::new(vec![Type::Number, Type::Number, Type::String], Type::Boolean),
+    );
     functions.insert("pumpEvents".to_string(), window::pump_events_builtin);
-    
+    signatures.insert("pumpEvents".to_string(), Signature::new(vec![], Type::Any));
 
     // Clock tick
     functions.insert(
         "setFramesPerSkibidi".to_string(),
         clock::clock_set_fps_builtin,
     );
+    signatures.insert(
+        "setFramesPerSkibidi".to_string(),
+        Signature::new(vec![Type::Number], Type::Boolean),
+    );
     functions.insert("clockEdge".to_string(), clock::clock_tick_builtin);
+    signatures.insert("clockEdge".to_string(), Signature::new(vec![], Type::Any));
+
+    // Coroutines, resumed by clockEdge every frame
+    functions.insert(
+        "startCoroutine".to_string(),
+        coroutine::start_coroutine_builtin,
+    );
+    signatures.insert(
+        "startCoroutine".to_string(),
+        Signature::new(vec![Type::Generator], Type::Boolean),
+    );
+    functions.insert("waitFrames".to_string(), coroutine::wait_frames_builtin);
+    signatures.insert(
+        "waitFrames".to_string(),
+        Signature::new(vec![Type::Number], Type::Any),
+    );
+    functions.insert("waitMs".to_string(), coroutine::wait_ms_builtin);
+    signatures.insert("waitMs".to_string(), Signature::new(vec![Type::Number], Type::Any));
+    functions.insert(
+        "tickCoroutines".to_string(),
+        coroutine::tick_coroutines_builtin,
+    );
+    signatures.insert(
+        "tickCoroutines".to_string(),
+        Signature::new(vec![], Type::Boolean),
+    );
+    functions.insert(
+        "coroutinesRunning".to_string(),
+        coroutine::coroutines_running_builtin,
+    );
+    signatures.insert(
+        "coroutinesRunning".to_string(),
+        Signature::new(vec![], Type::Number),
+    );
 
     // Draw
     functions.insert("goonScreen".to_string(), window::fill_screen_builtin);
+    signatures.insert(
+        "goonScreen".to_string(),
+        Signature::new(vec![Type::String], Type::Any),
+    );
     functions.insert("renderIn4k".to_string(), window::render_in_4k_builtin);
+    signatures.insert("renderIn4k".to_string(), Signature::new(vec![], Type::Boolean));
 
     // Shapes
     functions.insert("drawRect".to_string(), window::draw_rect_builtin);
+    signatures.insert(
+        "drawRect".to_string(),
+        Signature::new(
+            vec![Type::Number, Type::Number, Type::Number, Type::String],
+            Type::Boolean,
+        ),
+    );
 
     Library {
         functions,
+        signatures,
         state: LibState::SkuiState(SkuiState {
             app: None,
             event_loop: None,
             clock: None,
+            coroutines: Vec::new(),
         }),
     }
 }
 
-pub fn load_skui_state(itp: &mut Interpreter) -> Result<&mut SkuiState, error::ParseError> {
-    let line = itp.line;
+pub fn load_skui_state(itp: &mut Interpreter) -> Result<&mut SkuiState, error::RuntimeError> {
+    let span = itp.span;
     let libstate = get_lib_state(itp, LIBRARY_NAME);
 
     let state = if let LibState::SkuiState(state) = libstate {
         state
     } else {
-        return Err(error::ParseError::GeneralError {
-            line,
+        return Err(error::RuntimeError::GeneralError {
+            span,
             message: "Invalid state".to_string(),
         });
     };
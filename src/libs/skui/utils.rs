@@ -1,38 +1,38 @@
-use crate::error;
+use crate::{error, lexer::Span};
 
 use super::{SkuiApp, SkuiState};
 
-pub fn hex_to_rgba(hex: &str, line: usize) -> Result<[u8; 4], error::ParseError> {
+pub fn hex_to_rgba(hex: &str, span: Span) -> Result<[u8; 4], error::RuntimeError> {
     let hex = hex.trim_start_matches('#');
 
     if hex.len() != 6 {
-        return Err(error::ParseError::GeneralError {
-            line,
+        return Err(error::RuntimeError::GeneralError {
+            span,
             message: "Invalid hex color".to_string(),
         });
     }
 
-    let r = u8::from_str_radix(&hex[0..2], 16).map_err(|_| error::ParseError::GeneralError {
-        line,
+    let r = u8::from_str_radix(&hex[0..2], 16).map_err(|_| error::RuntimeError::GeneralError {
+        span,
         message: "Invalid red value in hex color".to_string(),
     })?;
 
-    let g = u8::from_str_radix(&hex[2..4], 16).map_err(|_| error::ParseError::GeneralError {
-        line,
+    let g = u8::from_str_radix(&hex[2..4], 16).map_err(|_| error::RuntimeError::GeneralError {
+        span,
         message: "Invalid green value in hex color".to_string(),
     })?;
 
-    let b = u8::from_str_radix(&hex[4..6], 16).map_err(|_| error::ParseError::GeneralError {
-        line,
+    let b = u8::from_str_radix(&hex[4..6], 16).map_err(|_| error::RuntimeError::GeneralError {
+        span,
         message: "Invalid blue value in hex color".to_string(),
     })?;
 
     Ok([r, g, b, 255]) // Full opacity (255)
 }
 
-pub fn get_app<'a>(state: &'a mut SkuiState) -> Result<&'a mut SkuiApp, error::ParseError> {
+pub fn get_app<'a>(state: &'a mut SkuiState) -> Result<&'a mut SkuiApp, error::RuntimeError> {
     state.app.as_mut().ok_or_else(|| {
-        error::ParseError::Other(
+        error::RuntimeError::Other(
             "App is not initialized. Have you created a window first?".to_string(),
         )
     })
@@ -10,15 +10,15 @@ use winit::platform::pump_events::{EventLoopExtPumpEvents, PumpStatus};
 pub fn create_window_builtin(
     itp: &mut Interpreter,
     args: Vec<Expr>,
-) -> Result<Expr, error::ParseError> {
+) -> Result<Expr, error::RuntimeError> {
     let width = itp.expr_to_number(itp.consume_argument(&args, 3, 0)?)? as u32;
     let height = itp.expr_to_number(itp.consume_argument(&args, 3, 1)?)? as u32;
     let title = itp.expr_to_string(itp.consume_argument(&args, 3, 2)?)?;
 
     // Ensure dimensions are valid
     if width <= 0 || height <= 0 {
-        return Err(error::ParseError::GeneralError {
-            line: itp.line,
+        return Err(error::RuntimeError::GeneralError {
+            span: itp.span,
             message: "Window dimensions must be greater than zero.".to_string(),
         });
     }
@@ -51,7 +51,7 @@ pub fn create_window_builtin(
 pub fn pump_events_builtin(
     itp: &mut Interpreter,
     _args: Vec<Expr>,
-) -> Result<Expr, error::ParseError> {
+) -> Result<Expr, error::RuntimeError> {
     let state = super::load_skui_state(itp)?;
 
     let event_loop = if let Some(event_loop) = &mut state.event_loop {
@@ -78,14 +78,14 @@ pub fn pump_events_builtin(
 pub fn fill_screen_builtin(
     itp: &mut Interpreter,
     args: Vec<Expr>,
-) -> Result<Expr, error::ParseError> {
-    let line = itp.line;
+) -> Result<Expr, error::RuntimeError> {
+    let span = itp.span;
 
     let colorhex = itp.expr_to_string(itp.consume_argument(&args, 1, 0)?)?;
 
     let state = super::load_skui_state(itp)?;
 
-    let rgba = super::utils::hex_to_rgba(&colorhex, line)?;
+    let rgba = super::utils::hex_to_rgba(&colorhex, span)?;
 
     let app = if let Some(app) = &mut state.app {
         app
@@ -112,7 +112,7 @@ pub fn fill_screen_builtin(
 pub fn render_in_4k_builtin(
     itp: &mut Interpreter,
     _args: Vec<Expr>,
-) -> Result<Expr, error::ParseError> {
+) -> Result<Expr, error::RuntimeError> {
     let state = super::load_skui_state(itp)?;
 
     let app = if let Some(app) = &mut state.app {
@@ -140,16 +140,16 @@ pub fn render_in_4k_builtin(
 pub fn draw_rect_builtin(
     itp: &mut Interpreter,
     args: Vec<Expr>,
-) -> Result<Expr, error::ParseError> {
+) -> Result<Expr, error::RuntimeError> {
     let size = itp.expr_to_number(itp.consume_argument(&args, 4, 0)?)?;
     let x = itp.expr_to_number(itp.consume_argument(&args, 4, 1)?)?;
     let y = itp.expr_to_number(itp.consume_argument(&args, 4, 2)?)?;
     let colorhex = itp.expr_to_string(itp.consume_argument(&args, 4, 3)?)?;
 
-    let line = itp.line;
+    let span = itp.span;
     let state = super::load_skui_state(itp)?;
 
-    let rgba = super::utils::hex_to_rgba(&colorhex, line)?;
+    let rgba = super::utils::hex_to_rgba(&colorhex, span)?;
 
     let app = super::utils::get_app(state)?;
     let window_info = app.window_info.as_ref().unwrap();
@@ -0,0 +1,862 @@
+// lint.rs
+
+// Static pass over the parsed statements that looks for things that are probably mistakes,
+// without running anything. These are warnings, the script still runs. Every lint has a name
+// and can be turned off on its own with --allow <name>.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    interpreter::BUILTINS,
+    lexer::Span,
+    libs,
+    parser::{Expr, Pattern, Stmt},
+    suggest,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintKind {
+    UndefinedFunction,    // cook of a cookable that doesn't exist
+    UnknownLibraryMember, // nerd.randit()
+    GoatOutsidePookie,    // goat in a cookable that isn't a method
+    UnreachableCode,      // Statements after blud
+    UnusedVariable,       // Parameters, goon and vibe check bindings that are never read
+    UnusedAssignment,     // Variables that are assigned but never read
+    UnusedFunction,       // Cookables nothing cooks
+    MissingEdge,          // A pookie without __edge__ can't be made with mew
+    GhostOutsideLoop,     // ghost that doesn't skip anything
+}
+
+impl LintKind {
+    pub const ALL: [LintKind; 9] = [
+        LintKind::UndefinedFunction,
+        LintKind::UnknownLibraryMember,
+        LintKind::GoatOutsidePookie,
+        LintKind::UnreachableCode,
+        LintKind::UnusedVariable,
+        LintKind::UnusedAssignment,
+        LintKind::UnusedFunction,
+        LintKind::MissingEdge,
+        LintKind::GhostOutsideLoop,
+    ];
+
+    // What it's called on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            LintKind::UndefinedFunction => "undefined_function",
+            LintKind::UnknownLibraryMember => "unknown_library_member",
+            LintKind::GoatOutsidePookie => "goat_outside_pookie",
+            LintKind::UnreachableCode => "unreachable_code",
+            LintKind::UnusedVariable => "unused_variable",
+            LintKind::UnusedAssignment => "unused_assignment",
+            LintKind::UnusedFunction => "unused_function",
+            LintKind::MissingEdge => "missing_edge",
+            LintKind::GhostOutsideLoop => "ghost_outside_loop",
+        }
+    }
+
+    // Like the error codes, see explain.rs
+    pub fn code(&self) -> &'static str {
+        match self {
+            LintKind::UndefinedFunction => "W0001",
+            LintKind::UnknownLibraryMember => "W0002",
+            LintKind::GoatOutsidePookie => "W0003",
+            LintKind::UnreachableCode => "W0004",
+            LintKind::UnusedVariable => "W0005",
+            LintKind::UnusedAssignment => "W0006",
+            LintKind::UnusedFunction => "W0007",
+            LintKind::MissingEdge => "W0008",
+            LintKind::GhostOutsideLoop => "W0009",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<LintKind> {
+        LintKind::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Lint {
+    pub kind: LintKind,
+    pub message: String,
+    pub span: Span,
+    pub help: Option<String>,
+}
+
+pub struct Linter {
+    functions: HashMap<String, Span>,            // Cookables outside of pookies
+    classes: HashMap<String, HashSet<String>>,   // Pookie name, its methods
+    libs: HashMap<String, Vec<String>>,          // Imported library, its functions
+    called: HashSet<String>,                     // Cookables cooked from somewhere other than themselves
+    reads: HashSet<String>,                      // Every name read anywhere in the script
+    assigned: HashMap<String, Span>,             // Variables that aren't locals, where they're first assigned
+
+    // State of where the linter is
+    current_function: Option<String>,
+    current_class: Option<String>,
+    locals: HashSet<String>,
+    loop_depth: usize,
+
+    lints: Vec<Lint>,
+}
+
+// Names starting with _ are unused on purpose
+fn is_ignored(name: &str) -> bool {
+    name.starts_with('_')
+}
+
+pub fn check(statements: &[Stmt], entry: &str) -> Vec<Lint> {
+    let mut linter = Linter::new();
+
+    linter.collect_declarations(statements);
+    collect_reads(statements, &mut linter.reads);
+    linter.check_block(statements);
+    linter.check_unused(entry);
+
+    linter
+        .lints
+        .sort_by_key(|lint| (lint.span.line, lint.span.column));
+
+    linter.lints
+}
+
+impl Linter {
+    pub fn new() -> Self {
+        Linter {
+            functions: HashMap::new(),
+            classes: HashMap::new(),
+            libs: HashMap::new(),
+            called: HashSet::new(),
+            reads: HashSet::new(),
+            assigned: HashMap::new(),
+            current_function: None,
+            current_class: None,
+            locals: HashSet::new(),
+            loop_depth: 0,
+            lints: Vec::new(),
+        }
+    }
+
+    fn warn(&mut self, kind: LintKind, message: String, span: Span, help: Option<String>) {
+        self.lints.push(Lint {
+            kind,
+            message,
+            span,
+            help,
+        });
+    }
+
+    // Cookables get added when their statement runs, so one declared anywhere can be cooked
+    fn collect_declarations(&mut self, statements: &[Stmt]) {
+        for stmt in statements {
+            match stmt {
+                Stmt::Function { name, body, span, .. } => {
+                    self.functions.entry(name.clone()).or_insert(*span);
+                    self.collect_declarations(body);
+                }
+                Stmt::Class {
+                    name, functions, ..
+                } => {
+                    let methods = functions
+                        .iter()
+                        .filter_map(|method| match method {
+                            Stmt::Function { name, .. } => Some(name.clone()),
+                            _ => None,
+                        })
+                        .collect();
+
+                    self.classes.insert(name.clone(), methods);
+                }
+                Stmt::Import { library, .. } => {
+                    if let Some(lib) = libs::load_library(library) {
+                        self.libs
+                            .insert(library.clone(), lib.functions.keys().cloned().collect());
+                    }
+                }
+                Stmt::While { body, .. } | Stmt::ForLoop { body, .. } => {
+                    self.collect_declarations(body)
+                }
+                Stmt::If {
+                    then_branch,
+                    else_branch,
+                    ..
+                } => {
+                    self.collect_declarations(then_branch);
+
+                    if let Some(else_branch) = else_branch {
+                        self.collect_declarations(else_branch);
+                    }
+                }
+                Stmt::Match { arms, .. } => {
+                    for arm in arms {
+                        self.collect_declarations(&arm.body);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn check_block(&mut self, statements: &[Stmt]) {
+        let mut unreachable = false;
+
+        for (index, stmt) in statements.iter().enumerate() {
+            self.check_statement(stmt);
+
+            // Everything after the first blud in the same block is dead
+            if let (Stmt::Return { .. }, Some(next), false) = (stmt, statements.get(index + 1), unreachable) {
+                let last = statements.last().unwrap_or(next);
+                unreachable = true;
+
+                self.warn(
+                    LintKind::UnreachableCode,
+                    "unreachable code".to_string(),
+                    next.span().to(last.span()),
+                    Some("everything after `blud` in the same block never runs".to_string()),
+                );
+            }
+        }
+    }
+
+    fn check_statement(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Class {
+                name,
+                functions,
+                span,
+            } => {
+                let has_edge = self
+                    .classes
+                    .get(name)
+                    .is_some_and(|methods| methods.contains("__edge__"));
+
+                if !has_edge {
+                    self.warn(
+                        LintKind::MissingEdge,
+                        format!("pookie {} has no __edge__", name),
+                        *span,
+                        Some(format!("`mew {}()` needs a `cookable __edge__()` to run", name)),
+                    );
+                }
+
+                let previous_class = self.current_class.replace(name.clone());
+                self.check_block(functions);
+                self.current_class = previous_class;
+            }
+            Stmt::Function {
+                name,
+                params,
+                body,
+                span,
+                ..
+            } => {
+                // Parameters, goon variables and bindings live in the call frame,
+                // everything else that gets assigned is a global
+                let mut locals: HashSet<String> = params.iter().map(|param| param.name.clone()).collect();
+                collect_bindings(body, &mut locals);
+
+                let mut body_reads = HashSet::new();
+                collect_reads(body, &mut body_reads);
+
+                for param in params {
+                    if !body_reads.contains(&param.name) && !is_ignored(&param.name) {
+                        self.warn(
+                            LintKind::UnusedVariable,
+                            format!("parameter {} of {} is never used", param.name, name),
+                            *span,
+                            Some(format!("call it _{} if it's unused on purpose", param.name)),
+                        );
+                    }
+                }
+
+                let previous_locals = std::mem::replace(&mut self.locals, locals);
+                let previous_function = self.current_function.replace(name.clone());
+                let previous_depth = std::mem::replace(&mut self.loop_depth, 0);
+
+                // A cookable inside a pookie is a method, anywhere else goat means nothing
+                let previous_class = if self.current_class.is_some() && previous_function.is_none() {
+                    self.current_class.clone()
+                } else {
+                    self.current_class.take()
+                };
+
+                self.check_block(body);
+
+                self.current_class = previous_class;
+                self.loop_depth = previous_depth;
+                self.current_function = previous_function;
+                self.locals = previous_locals;
+            }
+            Stmt::VariableAssign {
+                name,
+                object,
+                value,
+                span,
+                ..
+            } => {
+                match object {
+                    Some(object) => self.check_expression(object, *span),
+                    None => self.assign(name, *span),
+                }
+
+                self.check_expression(value, *span);
+            }
+            Stmt::Constant {
+                name, value, span, ..
+            } => {
+                self.assign(name, *span);
+                self.check_expression(value, *span);
+            }
+            Stmt::DestructureAssign { names, value, span } => {
+                for name in names {
+                    self.assign(name, *span);
+                }
+
+                self.check_expression(value, *span);
+            }
+            Stmt::While {
+                condition,
+                body,
+                span,
+            } => {
+                self.check_expression(condition, *span);
+                self.check_loop(body);
+            }
+            Stmt::ForLoop {
+                iterators,
+                collection,
+                body,
+                span,
+            } => {
+                self.check_expression(collection, *span);
+
+                let mut body_reads = HashSet::new();
+                collect_reads(body, &mut body_reads);
+                self.check_bindings(iterators, &body_reads, "goon variable", *span);
+
+                self.check_loop(body);
+            }
+            Stmt::If {
+                condition,
+                then_branch,
+                else_branch,
+                span,
+            } => {
+                self.check_expression(condition, *span);
+                self.check_block(then_branch);
+
+                if let Some(else_branch) = else_branch {
+                    self.check_block(else_branch);
+                }
+            }
+            Stmt::Match {
+                subject,
+                arms,
+                span,
+            } => {
+                self.check_expression(subject, *span);
+
+                for arm in arms {
+                    match &arm.pattern {
+                        Pattern::Variant { bindings, .. } => {
+                            let mut body_reads = HashSet::new();
+                            collect_reads(&arm.body, &mut body_reads);
+                            self.check_bindings(bindings, &body_reads, "binding", *span);
+                        }
+                        Pattern::Value(value) => self.check_expression(value, *span),
+                        Pattern::Wildcard => {}
+                    }
+
+                    self.check_block(&arm.body);
+                }
+            }
+            Stmt::Continue { span } => {
+                if self.loop_depth == 0 {
+                    self.warn(
+                        LintKind::GhostOutsideLoop,
+                        "ghost outside of a loop".to_string(),
+                        *span,
+                        Some("`ghost` only skips to the next round of a goon or skibidi loop".to_string()),
+                    );
+                }
+            }
+            Stmt::Expression { value, span }
+            | Stmt::Return { value, span }
+            | Stmt::Yield { value, span } => self.check_expression(value, *span),
+            Stmt::Import { .. } | Stmt::Enum { .. } => {}
+        }
+    }
+
+    fn check_loop(&mut self, body: &[Stmt]) {
+        self.loop_depth += 1;
+        self.check_block(body);
+        self.loop_depth -= 1;
+    }
+
+    // Names bound for a block, that the block never reads
+    fn check_bindings(&mut self, names: &[String], reads: &HashSet<String>, what: &str, span: Span) {
+        for name in names {
+            if !reads.contains(name) && !is_ignored(name) {
+                self.warn(
+                    LintKind::UnusedVariable,
+                    format!("{} {} is never used", what, name),
+                    span,
+                    Some(format!("call it _{} if it's unused on purpose", name)),
+                );
+            }
+        }
+    }
+
+    fn assign(&mut self, name: &str, span: Span) {
+        if !self.locals.contains(name) {
+            self.assigned.entry(name.to_string()).or_insert(span);
+        }
+    }
+
+    fn check_expression(&mut self, expr: &Expr, span: Span) {
+        match expr {
+            Expr::Spanned { expr, span } => self.check_expression(expr, *span),
+            Expr::Ident(name) => {
+                if name == "goat" && self.current_class.is_none() {
+                    self.warn(
+                        LintKind::GoatOutsidePookie,
+                        "goat used outside of a pookie".to_string(),
+                        span,
+                        Some("`goat` is only there inside the cookables of a pookie".to_string()),
+                    );
+                }
+            }
+            Expr::FunctionCall { name, object, args } => {
+                match object {
+                    Some(object) => {
+                        self.check_expression(object, span);
+                        self.check_method_call(name, object, span);
+                    }
+                    None => self.check_call(name, span),
+                }
+
+                for arg in args {
+                    self.check_expression(arg, span);
+                }
+            }
+            Expr::List(elements) => {
+                for element in elements {
+                    self.check_expression(element, span);
+                }
+            }
+            Expr::NewInstance { args, .. } => {
+                for arg in args {
+                    self.check_expression(arg, span);
+                }
+            }
+            Expr::EnumVariant { payload, .. } => {
+                for value in payload {
+                    self.check_expression(value, span);
+                }
+            }
+            Expr::BinOp { left, right, .. } => {
+                self.check_expression(left, span);
+                self.check_expression(right, span);
+            }
+            Expr::ObjectValue { object, .. } | Expr::Not(object) => self.check_expression(object, span),
+            Expr::Range {
+                start, end, step, ..
+            } => {
+                for bound in [Some(start), Some(end), step.as_ref()].into_iter().flatten() {
+                    self.check_expression(bound, span);
+                }
+            }
+            Expr::Number(_)
+            | Expr::BigNumber(_)
+            | Expr::StringLiteral(_)
+            | Expr::Boolean(_)
+            | Expr::Instance { .. }
+            | Expr::Generator { .. }
+            | Expr::Wait(_) => {}
+        }
+    }
+
+    fn check_call(&mut self, name: &str, span: Span) {
+        if BUILTINS.contains(&name) {
+            return;
+        }
+
+        if self.functions.contains_key(name) {
+            if self.current_function.as_deref() != Some(name) {
+                self.called.insert(name.to_string());
+            }
+
+            return;
+        }
+
+        let candidates = self
+            .functions
+            .keys()
+            .map(String::as_str)
+            .chain(BUILTINS.iter().copied());
+        let help = suggest::did_you_mean(name, candidates).map(|name| format!("did you mean `{}`?", name));
+
+        self.warn(
+            LintKind::UndefinedFunction,
+            format!("cookable {} is not defined", name),
+            span,
+            help,
+        );
+    }
+
+    // Only library calls and goat's own methods can be checked, anything else depends on the value
+    fn check_method_call(&mut self, name: &str, object: &Expr, span: Span) {
+        let object_name = match object.unspanned() {
+            Expr::Ident(object_name) => object_name,
+            _ => return,
+        };
+
+        if let Some(functions) = self.libs.get(object_name) {
+            if !functions.iter().any(|function| function == name) {
+                let help = suggest::did_you_mean(name, functions.iter().map(String::as_str))
+                    .map(|name| format!("did you mean `{}.{}`?", object_name, name));
+
+                self.warn(
+                    LintKind::UnknownLibraryMember,
+                    format!("{} has no cookable {}", object_name, name),
+                    span,
+                    help,
+                );
+            }
+
+            return;
+        }
+
+        if object_name != "goat" {
+            return;
+        }
+
+        let methods = match self.current_class.as_ref().and_then(|class| self.classes.get(class)) {
+            Some(methods) => methods,
+            None => return,
+        };
+
+        if !methods.contains(name) {
+            let help = suggest::did_you_mean(name, methods.iter().map(String::as_str))
+                .map(|name| format!("did you mean `goat.{}`?", name));
+            let class = self.current_class.clone().unwrap_or_default();
+
+            self.warn(
+                LintKind::UndefinedFunction,
+                format!("pookie {} has no cookable {}", class, name),
+                span,
+                help,
+            );
+        }
+    }
+
+    // Done once everything has been seen, since a cookable can be cooked before it's declared
+    fn check_unused(&mut self, entry: &str) {
+        let mut unused_functions: Vec<(String, Span)> = self
+            .functions
+            .iter()
+            .filter(|(name, _)| {
+                name.as_str() != entry && !self.called.contains(*name) && !is_ignored(name)
+            })
+            .map(|(name, span)| (name.clone(), *span))
+            .collect();
+        unused_functions.sort_by_key(|(_, span)| (span.line, span.column));
+
+        for (name, span) in unused_functions {
+            self.warn(
+                LintKind::UnusedFunction,
+                format!("cookable {} is never cooked", name),
+                span,
+                None,
+            );
+        }
+
+        let mut unused_variables: Vec<(String, Span)> = self
+            .assigned
+            .iter()
+            .filter(|(name, _)| !self.reads.contains(*name) && !is_ignored(name))
+            .map(|(name, span)| (name.clone(), *span))
+            .collect();
+        unused_variables.sort_by_key(|(_, span)| (span.line, span.column));
+
+        for (name, span) in unused_variables {
+            self.warn(
+                LintKind::UnusedAssignment,
+                format!("{} is assigned but never read", name),
+                span,
+                Some(format!("call it _{} if it's unused on purpose", name)),
+            );
+        }
+    }
+}
+
+// Names that go into the call frame instead of the globals, not counting nested cookables
+fn collect_bindings(statements: &[Stmt], locals: &mut HashSet<String>) {
+    for stmt in statements {
+        match stmt {
+            Stmt::ForLoop {
+                iterators, body, ..
+            } => {
+                locals.extend(iterators.iter().cloned());
+                collect_bindings(body, locals);
+            }
+            Stmt::While { body, .. } => collect_bindings(body, locals),
+            Stmt::If {
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                collect_bindings(then_branch, locals);
+
+                if let Some(else_branch) = else_branch {
+                    collect_bindings(else_branch, locals);
+                }
+            }
+            Stmt::Match { arms, .. } => {
+                for arm in arms {
+                    if let Pattern::Variant { bindings, .. } = &arm.pattern {
+                        locals.extend(bindings.iter().cloned());
+                    }
+
+                    collect_bindings(&arm.body, locals);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Every name that's read, including objects that get a field assigned or a method cooked
+fn collect_reads(statements: &[Stmt], reads: &mut HashSet<String>) {
+    for stmt in statements {
+        match stmt {
+            Stmt::Class { functions: body, .. } | Stmt::Function { body, .. } => {
+                collect_reads(body, reads)
+            }
+            Stmt::VariableAssign { object, value, .. } => {
+                if let Some(object) = object {
+                    collect_expression_reads(object, reads);
+                }
+
+                collect_expression_reads(value, reads);
+            }
+            Stmt::Constant { value, .. }
+            | Stmt::DestructureAssign { value, .. }
+            | Stmt::Expression { value, .. }
+            | Stmt::Return { value, .. }
+            | Stmt::Yield { value, .. } => collect_expression_reads(value, reads),
+            Stmt::While {
+                condition: value,
+                body,
+                ..
+            }
+            | Stmt::ForLoop {
+                collection: value,
+                body,
+                ..
+            } => {
+                collect_expression_reads(value, reads);
+                collect_reads(body, reads);
+            }
+            Stmt::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                collect_expression_reads(condition, reads);
+                collect_reads(then_branch, reads);
+
+                if let Some(else_branch) = else_branch {
+                    collect_reads(else_branch, reads);
+                }
+            }
+            Stmt::Match { subject, arms, .. } => {
+                collect_expression_reads(subject, reads);
+
+                for arm in arms {
+                    if let Pattern::Value(value) = &arm.pattern {
+                        collect_expression_reads(value, reads);
+                    }
+
+                    collect_reads(&arm.body, reads);
+                }
+            }
+            Stmt::Import { .. } | Stmt::Continue { .. } | Stmt::Enum { .. } => {}
+        }
+    }
+}
+
+fn collect_expression_reads(expr: &Expr, reads: &mut HashSet<String>) {
+    match expr {
+        Expr::Ident(name) => {
+            reads.insert(name.clone());
+        }
+        Expr::Spanned { expr, .. } | Expr::Not(expr) => collect_expression_reads(expr, reads),
+        Expr::FunctionCall { object, args, .. } => {
+            if let Some(object) = object {
+                collect_expression_reads(object, reads);
+            }
+
+            for arg in args {
+                collect_expression_reads(arg, reads);
+            }
+        }
+        Expr::List(values)
+        | Expr::NewInstance { args: values, .. }
+        | Expr::EnumVariant {
+            payload: values, ..
+        } => {
+            for value in values {
+                collect_expression_reads(value, reads);
+            }
+        }
+        Expr::BinOp { left, right, .. } => {
+            collect_expression_reads(left, reads);
+            collect_expression_reads(right, reads);
+        }
+        Expr::ObjectValue { object, .. } => collect_expression_reads(object, reads),
+        Expr::Range {
+            start, end, step, ..
+        } => {
+            for bound in [Some(start), Some(end), step.as_ref()].into_iter().flatten() {
+                collect_expression_reads(bound, reads);
+            }
+        }
+        Expr::Number(_)
+        | Expr::BigNumber(_)
+        | Expr::StringLiteral(_)
+        | Expr::Boolean(_)
+        | Expr::Instance { .. }
+        | Expr::Generator { .. }
+        | Expr::Wait(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::Lexer, parser::Parser};
+
+    // Lint names and the lines they're on
+    fn lints(source: &str) -> Vec<(&'static str, usize)> {
+        let mut lexer = Lexer::new(source);
+        let (statements, errors) = Parser::new(&mut lexer).unwrap().parse();
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        check(&statements, "fein")
+            .iter()
+            .map(|lint| (lint.kind.name(), lint.span.line))
+            .collect()
+    }
+
+    #[test]
+    fn clean_script() {
+        let source = "\
+gyatt nerd
+
+pookie P()
+    cookable __edge__(x)
+        goat.x is x
+    slay
+slay
+
+cookable fein(args)
+    p is mew P(cook nerd.randInt(1, 3))
+    goon (_, arg in args) eat
+        cook yap(arg, p)
+    slay
+slay
+";
+
+        assert_eq!(lints(source), vec![]);
+    }
+
+    #[test]
+    fn undefined_functions() {
+        let source = "\
+gyatt nerd
+
+cookable fein()
+    cook yapp(1)
+    cook nerd.randit(1, 2)
+slay
+";
+
+        assert_eq!(lints(source), vec![("undefined_function", 4), ("unknown_library_member", 5)]);
+    }
+
+    #[test]
+    fn goat_and_ghost_out_of_place() {
+        let source = "\
+cookable fein()
+    cook yap(goat.x)
+    ghost
+slay
+";
+
+        assert_eq!(lints(source), vec![("goat_outside_pookie", 2), ("ghost_outside_loop", 3)]);
+    }
+
+    #[test]
+    fn unreachable_code() {
+        let source = "\
+cookable fein()
+    blud 1
+    cook yap(2)
+slay
+";
+
+        assert_eq!(lints(source), vec![("unreachable_code", 3)]);
+    }
+
+    #[test]
+    fn unused_things() {
+        let source = "\
+total is 0
+
+cookable helper(x, _y)
+slay
+
+cookable fein()
+    goon (i in 0..3) eat
+    slay
+slay
+";
+
+        assert_eq!(
+            lints(source),
+            vec![
+                ("unused_assignment", 1),
+                ("unused_variable", 3),
+                ("unused_function", 3),
+                ("unused_variable", 7)
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_edge() {
+        let source = "\
+pookie P()
+    cookable go()
+    slay
+slay
+
+cookable fein()
+    p is mew P()
+    cook p.go()
+slay
+";
+
+        assert_eq!(lints(source), vec![("missing_edge", 1)]);
+    }
+
+    #[test]
+    fn lint_names_round_trip() {
+        for kind in LintKind::ALL {
+            assert_eq!(LintKind::from_name(kind.name()), Some(kind));
+        }
+        assert_eq!(LintKind::from_name("unused"), None);
+    }
+}
@@ -0,0 +1,928 @@
+// lsp.rs
+
+// `skibidyscript lsp`, a language server so editors can show errors while you type, jump to
+// cookables and pookies, explain library cookables and complete names. It speaks JSON-RPC over
+// stdin and stdout, every message has a Content-Length header in front of it.
+//
+// Editors send the whole script on every change and it's parsed from scratch each time, scripts
+// aren't big enough for anything smarter. Lines and characters are 0 based here where spans are
+// 1 based. Characters are counted in chars and not the UTF-16 units the protocol wants, so they're
+// only off on a line with an emoji before the spot.
+
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, Write},
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    diagnostic::{Diagnostic, Severity},
+    formatter,
+    interpreter::BUILTINS,
+    lexer::{Comment, Lexer, Span},
+    libs::{self, Signature},
+    lint,
+    parser::{Expr, Parser, Stmt},
+    suggest, typecheck,
+};
+
+// JSON-RPC error for a request the server doesn't know
+const METHOD_NOT_FOUND: i64 = -32601;
+
+// The numbers the protocol uses for kinds of completions and symbols, only the ones used here
+const COMPLETION_METHOD: i64 = 2;
+const COMPLETION_FUNCTION: i64 = 3;
+const COMPLETION_FIELD: i64 = 5;
+const COMPLETION_CLASS: i64 = 7;
+const COMPLETION_MODULE: i64 = 9;
+const COMPLETION_ENUM: i64 = 13;
+const COMPLETION_KEYWORD: i64 = 14;
+const COMPLETION_ENUM_MEMBER: i64 = 20;
+
+const SYMBOL_CLASS: i64 = 5;
+const SYMBOL_METHOD: i64 = 6;
+const SYMBOL_ENUM: i64 = 10;
+const SYMBOL_FUNCTION: i64 = 12;
+const SYMBOL_CONSTANT: i64 = 14;
+const SYMBOL_ENUM_MEMBER: i64 = 22;
+
+pub fn run() -> i32 {
+    let mut server = Server {
+        documents: HashMap::new(),
+        shutting_down: false,
+    };
+
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+
+    loop {
+        let message = match read_message(&mut input) {
+            Ok(Some(message)) => message,
+            // The editor went away without saying exit
+            Ok(None) => return 1,
+            Err(e) => {
+                eprintln!("error: couldn't read a message: {}", e);
+
+                return 1;
+            }
+        };
+
+        if let Some(code) = server.handle(message) {
+            return code;
+        }
+    }
+}
+
+// The debug adapter frames its messages the same way, so it uses these too
+pub fn read_message(input: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut length = None;
+
+    // Headers, then a blank line, then the body
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+
+    let length = length.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no Content-Length"))?;
+
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+pub fn send(message: Value) {
+    let body = message.to_string();
+
+    let mut stdout = io::stdout().lock();
+    let _ = write!(stdout, "Content-Length: {}\r\n\r\n{}", body.len(), body);
+    let _ = stdout.flush();
+}
+
+struct Server {
+    documents: HashMap<String, String>, // uri, text
+    shutting_down: bool,
+}
+
+impl Server {
+    // Some(code) once it's time to exit
+    fn handle(&mut self, message: Value) -> Option<i32> {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+
+        // Requests have an id and want an answer, notifications don't.
+        // Answers to requests of our own have no method, and there are none
+        let id = match message.get("id") {
+            Some(id) if !method.is_empty() => id.clone(),
+            Some(_) => return None,
+            None => return self.notification(method, params),
+        };
+
+        let result = match method {
+            "initialize" => capabilities(),
+            "shutdown" => {
+                self.shutting_down = true;
+
+                Value::Null
+            }
+            "textDocument/hover" => self.hover(params).unwrap_or(Value::Null),
+            "textDocument/definition" => self.definition(params).unwrap_or(Value::Null),
+            "textDocument/completion" => self.completion(params).unwrap_or(Value::Null),
+            "textDocument/documentSymbol" => self.symbols(params).unwrap_or(Value::Null),
+            "textDocument/formatting" => self.formatting(params).unwrap_or(Value::Null),
+            _ => {
+                send(json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": {
+                        "code": METHOD_NOT_FOUND,
+                        "message": format!("{} isn't supported", method),
+                    },
+                }));
+
+                return None;
+            }
+        };
+
+        send(json!({ "jsonrpc": "2.0", "id": id, "result": result }));
+
+        None
+    }
+
+    fn notification(&mut self, method: &str, params: &Value) -> Option<i32> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default().to_string();
+
+        match method {
+            "exit" => return Some(if self.shutting_down { 0 } else { 1 }),
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+
+                self.documents.insert(uri.clone(), text.to_string());
+                self.publish_diagnostics(&uri);
+            }
+            // The whole text every time, that's what initialize asked for
+            "textDocument/didChange" => {
+                let changes = params["contentChanges"].as_array();
+
+                if let Some(text) = changes.and_then(|changes| changes.last()?["text"].as_str()) {
+                    self.documents.insert(uri.clone(), text.to_string());
+                    self.publish_diagnostics(&uri);
+                }
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+
+                send(json!({
+                    "jsonrpc": "2.0",
+                    "method": "textDocument/publishDiagnostics",
+                    "params": { "uri": uri, "diagnostics": [] },
+                }));
+            }
+            _ => {}
+        }
+
+        None
+    }
+
+    // The script and the cursor a request is about
+    fn document<'a>(&'a self, params: &'a Value) -> Option<(&'a str, &'a str, usize, usize)> {
+        let uri = params["textDocument"]["uri"].as_str()?;
+        let text = self.documents.get(uri)?;
+        let line = params["position"]["line"].as_u64().unwrap_or_default() as usize;
+        let character = params["position"]["character"].as_u64().unwrap_or_default() as usize;
+
+        Some((uri, text, line, character))
+    }
+
+    // Errors from the parser, and if it's happy the lints and type checker too
+    fn publish_diagnostics(&self, uri: &str) {
+        let text = match self.documents.get(uri) {
+            Some(text) => text,
+            None => return,
+        };
+
+        let diagnostics: Vec<Value> = parse(text).diagnostics.iter().map(lsp_diagnostic).collect();
+
+        send(json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        }));
+    }
+
+    fn hover(&self, params: &Value) -> Option<Value> {
+        let (_, text, line, character) = self.document(params)?;
+        let word = word_at(text, line, character)?;
+        let statements = parse(text).statements;
+
+        let contents = match &word.object {
+            Some(object) => member_hover(&statements, object, &word.name)?,
+            None => name_hover(&statements, &word.name)?,
+        };
+
+        Some(json!({
+            "contents": {
+                "kind": "markdown",
+                "value": format!("```skibidiscript\n{}\n```", contents),
+            },
+            "range": {
+                "start": { "line": line, "character": word.start },
+                "end": { "line": line, "character": word.end },
+            },
+        }))
+    }
+
+    fn definition(&self, params: &Value) -> Option<Value> {
+        let (uri, text, line, character) = self.document(params)?;
+        let word = word_at(text, line, character)?;
+        let statements = parse(text).statements;
+
+        let span = match &word.object {
+            // goat.run goes to the pookie it's in first
+            Some(object) => {
+                let enclosing = match object.as_str() {
+                    "goat" => enclosing_class(&statements, line + 1),
+                    _ => None,
+                };
+
+                enclosing
+                    .into_iter()
+                    .chain(&statements)
+                    .find_map(|stmt| member_definition(stmt, object, &word.name))?
+            }
+            None => statements.iter().find_map(|stmt| match stmt {
+                Stmt::Function { name, span, .. }
+                | Stmt::Class { name, span, .. }
+                | Stmt::Enum { name, span, .. }
+                    if *name == word.name =>
+                {
+                    Some(*span)
+                }
+                _ => None,
+            })?,
+        };
+
+        Some(json!({ "uri": uri, "range": name_range(text, span, &word.name) }))
+    }
+
+    fn completion(&self, params: &Value) -> Option<Value> {
+        let (_, text, line, character) = self.document(params)?;
+        let chars: Vec<char> = line_text(text, line)?.chars().collect();
+        let character = character.min(chars.len());
+
+        // Whatever has been typed of the name so far is the editor's to filter on
+        let start = character - chars[..character].iter().rev().take_while(|ch| is_name(ch)).count();
+        let statements = parse(text).statements;
+
+        let items = match name_before_dot(&chars, start) {
+            Some(object) => member_completions(&statements, &object, line + 1),
+            None => global_completions(&statements),
+        };
+
+        Some(json!(items))
+    }
+
+    fn symbols(&self, params: &Value) -> Option<Value> {
+        let uri = params["textDocument"]["uri"].as_str()?;
+        let text = self.documents.get(uri)?;
+
+        let symbols: Vec<Value> = parse(text)
+            .statements
+            .iter()
+            .filter_map(|stmt| symbol(text, stmt))
+            .collect();
+
+        Some(json!(symbols))
+    }
+
+    // One edit that replaces everything, nothing if there's a syntax error
+    fn formatting(&self, params: &Value) -> Option<Value> {
+        let uri = params["textDocument"]["uri"].as_str()?;
+        let text = self.documents.get(uri)?;
+
+        let parsed = parse(text);
+        if parsed.failed {
+            return None;
+        }
+
+        let formatted = formatter::format(text, &parsed.statements, &parsed.comments);
+        if formatted == *text {
+            return Some(json!([]));
+        }
+
+        Some(json!([{
+            "range": {
+                "start": { "line": 0, "character": 0 },
+                "end": { "line": text.split('\n').count(), "character": 0 },
+            },
+            "newText": formatted,
+        }]))
+    }
+}
+
+fn capabilities() -> Value {
+    json!({
+        "capabilities": {
+            "textDocumentSync": 1, // Full text on every change
+            "hoverProvider": true,
+            "definitionProvider": true,
+            "completionProvider": { "triggerCharacters": ["."] },
+            "documentSymbolProvider": true,
+            "documentFormattingProvider": true,
+        },
+        "serverInfo": {
+            "name": "skibidyscript",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+    })
+}
+
+struct Parsed {
+    statements: Vec<Stmt>, // Only what could be made sense of when it failed
+    comments: Vec<Comment>,
+    diagnostics: Vec<Diagnostic>,
+    failed: bool,
+}
+
+fn parse(text: &str) -> Parsed {
+    let mut lexer = Lexer::new(text);
+
+    let (statements, errors) = match Parser::new(&mut lexer) {
+        Ok(mut parser) => parser.parse(),
+        Err(e) => (Vec::new(), vec![e]),
+    };
+
+    let mut diagnostics: Vec<Diagnostic> = errors.iter().map(Diagnostic::from).collect();
+
+    // Half a script would only get half the warnings wrong
+    if errors.is_empty() {
+        diagnostics.extend(lint::check(&statements, "fein").iter().map(Diagnostic::from));
+        diagnostics.extend(typecheck::check(&statements).iter().map(Diagnostic::from));
+    }
+
+    Parsed {
+        statements,
+        comments: std::mem::take(&mut lexer.comments),
+        diagnostics,
+        failed: !errors.is_empty(),
+    }
+}
+
+fn lsp_diagnostic(diagnostic: &Diagnostic) -> Value {
+    let mut message = diagnostic.message.clone();
+
+    for note in &diagnostic.notes {
+        message += &format!("\nnote: {}", note);
+    }
+
+    for help in &diagnostic.help {
+        message += &format!("\nhelp: {}", help);
+    }
+
+    json!({
+        "range": range(diagnostic.span.unwrap_or_default()),
+        "severity": match diagnostic.severity {
+            Severity::Error => 1,
+            Severity::Warning => 2,
+        },
+        "code": diagnostic.code,
+        "source": "skibidiscript",
+        "message": message,
+    })
+}
+
+fn range(span: Span) -> Value {
+    let position = |line: usize, column: usize| {
+        json!({ "line": line.saturating_sub(1), "character": column.saturating_sub(1) })
+    };
+
+    // Some errors only know where they start
+    let end = match span.end_line {
+        0 => position(span.line, span.column),
+        _ => position(span.end_line, span.end_column),
+    };
+
+    json!({ "start": position(span.line, span.column), "end": end })
+}
+
+// Where the name is on the first line of what it names, cookable |fein|()
+fn name_range(text: &str, span: Span, name: &str) -> Value {
+    let line = span.line.saturating_sub(1);
+    let chars: Vec<char> = line_text(text, line).unwrap_or_default().chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    let start = (span.column.saturating_sub(1)..chars.len())
+        .find(|&start| chars[start..].starts_with(&name))
+        .unwrap_or(span.column.saturating_sub(1));
+
+    json!({
+        "start": { "line": line, "character": start },
+        "end": { "line": line, "character": start + name.len() },
+    })
+}
+
+fn line_text(text: &str, line: usize) -> Option<&str> {
+    text.split('\n').nth(line).map(|text| text.trim_end_matches('\r'))
+}
+
+fn is_name(ch: &char) -> bool {
+    ch.is_alphanumeric() || *ch == '_'
+}
+
+// The name under the cursor, and the name in front of it with a dot between if there is one
+struct Word {
+    name: String,
+    object: Option<String>,
+    start: usize,
+    end: usize,
+}
+
+fn word_at(text: &str, line: usize, character: usize) -> Option<Word> {
+    let chars: Vec<char> = line_text(text, line)?.chars().collect();
+    let character = character.min(chars.len());
+
+    let start = character - chars[..character].iter().rev().take_while(|ch| is_name(ch)).count();
+    let end = character + chars[character..].iter().take_while(|ch| is_name(ch)).count();
+
+    if start == end {
+        return None;
+    }
+
+    Some(Word {
+        name: chars[start..end].iter().collect(),
+        object: name_before_dot(&chars, start),
+        start,
+        end,
+    })
+}
+
+fn name_before_dot(chars: &[char], start: usize) -> Option<String> {
+    if start == 0 || chars[start - 1] != '.' {
+        return None;
+    }
+
+    let end = start - 1;
+    let begin = end - chars[..end].iter().rev().take_while(|ch| is_name(ch)).count();
+
+    (begin < end).then(|| chars[begin..end].iter().collect())
+}
+
+// The pookie a line is inside of
+fn enclosing_class(statements: &[Stmt], line: usize) -> Option<&Stmt> {
+    statements.iter().find(|stmt| {
+        let span = stmt.span();
+        matches!(stmt, Stmt::Class { .. }) && span.line <= line && line <= span.end_line
+    })
+}
+
+// Methods of a pookie, and the variants of a vibe
+fn member_definition(stmt: &Stmt, object: &str, member: &str) -> Option<Span> {
+    match stmt {
+        Stmt::Class { functions, .. } => functions.iter().find_map(|function| match function {
+            Stmt::Function { name, span, .. } if name == member => Some(*span),
+            _ => None,
+        }),
+        Stmt::Enum { name, variants, .. } if name == object => variants
+            .iter()
+            .find(|variant| variant.name == member)
+            .map(|variant| variant.span),
+        _ => None,
+    }
+}
+
+fn library_signature(library: &str, name: &str, signature: &Signature) -> String {
+    let params: Vec<String> = signature.params.iter().map(|param| param.to_string()).collect();
+
+    format!("{}.{}({}) -> {}", library, name, params.join(", "), signature.returns)
+}
+
+// A library's cookables, sorted so they come out the same every time
+fn library_signatures(library: &str) -> Option<Vec<(String, String)>> {
+    let lib = libs::load_library(library)?;
+
+    let mut signatures: Vec<(String, String)> = lib
+        .signatures
+        .iter()
+        .map(|(name, signature)| (name.clone(), library_signature(library, name, signature)))
+        .collect();
+    signatures.sort();
+
+    Some(signatures)
+}
+
+fn member_hover(statements: &[Stmt], object: &str, member: &str) -> Option<String> {
+    if let Some(signatures) = library_signatures(object) {
+        return signatures
+            .into_iter()
+            .find(|(name, _)| name == member)
+            .map(|(_, signature)| signature);
+    }
+
+    statements.iter().find_map(|stmt| match stmt {
+        Stmt::Class { name, functions, .. } => functions.iter().find_map(|function| match function {
+            Stmt::Function {
+                name: method,
+                params,
+                return_type,
+                ..
+            } if method == member => Some(format!(
+                "pookie {}()\n    {}",
+                name,
+                formatter::function_header(method, params, return_type)
+            )),
+            _ => None,
+        }),
+        Stmt::Enum { name, variants, .. } if name == object => variants
+            .iter()
+            .find(|variant| variant.name == member)
+            .map(|variant| format!("{}.{}", name, variant_text(&variant.name, &variant.fields))),
+        _ => None,
+    })
+}
+
+fn name_hover(statements: &[Stmt], name: &str) -> Option<String> {
+    if let Some(signatures) = library_signatures(name) {
+        let signatures: Vec<String> = signatures.into_iter().map(|(_, signature)| signature).collect();
+
+        return Some(format!("gyatt {}\n\n{}", name, signatures.join("\n")));
+    }
+
+    statements.iter().find_map(|stmt| match stmt {
+        Stmt::Function {
+            name: function,
+            params,
+            return_type,
+            ..
+        } if function == name => Some(formatter::function_header(function, params, return_type)),
+        Stmt::Class {
+            name: class,
+            functions,
+            ..
+        } if class == name => {
+            let methods: Vec<String> = functions
+                .iter()
+                .filter_map(|function| match function {
+                    Stmt::Function {
+                        name, params, return_type, ..
+                    } => Some(format!("    {}", formatter::function_header(name, params, return_type))),
+                    _ => None,
+                })
+                .collect();
+
+            Some(format!("pookie {}()\n{}", class, methods.join("\n")))
+        }
+        Stmt::Enum {
+            name: vibe, variants, ..
+        } if vibe == name => {
+            let variants: Vec<String> = variants
+                .iter()
+                .map(|variant| format!("    {}", variant_text(&variant.name, &variant.fields)))
+                .collect();
+
+            Some(format!("vibe {}\n{}", vibe, variants.join("\n")))
+        }
+        _ => None,
+    })
+}
+
+fn variant_text(name: &str, fields: &[String]) -> String {
+    if fields.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}({})", name, fields.join(", "))
+    }
+}
+
+fn completion_item(label: &str, kind: i64, detail: Option<String>) -> Value {
+    json!({ "label": label, "kind": kind, "detail": detail })
+}
+
+// After a dot: what a library has, the fields and methods of goat, or the variants of a vibe
+fn member_completions(statements: &[Stmt], object: &str, line: usize) -> Vec<Value> {
+    if let Some(signatures) = library_signatures(object) {
+        return signatures
+            .into_iter()
+            .map(|(name, signature)| completion_item(&name, COMPLETION_FUNCTION, Some(signature)))
+            .collect();
+    }
+
+    if object == "goat" {
+        let functions = match enclosing_class(statements, line) {
+            Some(Stmt::Class { functions, .. }) => functions,
+            _ => return Vec::new(),
+        };
+
+        let mut fields = Vec::new();
+        goat_fields(functions, &mut fields);
+        fields.sort();
+        fields.dedup();
+
+        let mut items: Vec<Value> = fields
+            .iter()
+            .map(|field| completion_item(field, COMPLETION_FIELD, None))
+            .collect();
+
+        for function in functions {
+            if let Stmt::Function {
+                name, params, return_type, ..
+            } = function
+            {
+                let header = formatter::function_header(name, params, return_type);
+                items.push(completion_item(name, COMPLETION_METHOD, Some(header)));
+            }
+        }
+
+        return items;
+    }
+
+    statements
+        .iter()
+        .find_map(|stmt| match stmt {
+            Stmt::Enum { name, variants, .. } if name == object => Some(
+                variants
+                    .iter()
+                    .map(|variant| {
+                        let detail = variant_text(&variant.name, &variant.fields);
+                        completion_item(&variant.name, COMPLETION_ENUM_MEMBER, Some(detail))
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+// Everything goat.x is assigned to, anywhere in the pookie's methods
+fn goat_fields(statements: &[Stmt], fields: &mut Vec<String>) {
+    for stmt in statements {
+        match stmt {
+            Stmt::VariableAssign {
+                name,
+                object: Some(object),
+                ..
+            } if matches!(object.unspanned(), Expr::Ident(goat) if goat == "goat") => {
+                fields.push(name.clone());
+            }
+            Stmt::Function { body, .. }
+            | Stmt::While { body, .. }
+            | Stmt::ForLoop { body, .. } => goat_fields(body, fields),
+            Stmt::If {
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                goat_fields(then_branch, fields);
+                goat_fields(else_branch.as_deref().unwrap_or_default(), fields);
+            }
+            Stmt::Match { arms, .. } => {
+                for arm in arms {
+                    goat_fields(&arm.body, fields);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Keywords, builtins, libraries and whatever the script declares at the top
+fn global_completions(statements: &[Stmt]) -> Vec<Value> {
+    let mut keywords = suggest::keywords();
+    keywords.sort();
+
+    let mut items: Vec<Value> = keywords
+        .iter()
+        .map(|keyword| completion_item(keyword, COMPLETION_KEYWORD, None))
+        .collect();
+
+    items.extend(BUILTINS.iter().map(|name| completion_item(name, COMPLETION_FUNCTION, None)));
+    items.extend(libs::LIBRARY_NAMES.iter().map(|name| completion_item(name, COMPLETION_MODULE, None)));
+
+    for stmt in statements {
+        match stmt {
+            Stmt::Function {
+                name, params, return_type, ..
+            } => {
+                let header = formatter::function_header(name, params, return_type);
+                items.push(completion_item(name, COMPLETION_FUNCTION, Some(header)));
+            }
+            Stmt::Class { name, .. } => items.push(completion_item(name, COMPLETION_CLASS, None)),
+            Stmt::Enum { name, .. } => items.push(completion_item(name, COMPLETION_ENUM, None)),
+            _ => {}
+        }
+    }
+
+    items
+}
+
+// The outline, cookables, pookies with their methods, vibes with their variants and constants
+fn symbol(text: &str, stmt: &Stmt) -> Option<Value> {
+    let document_symbol = |name: &str, detail: String, kind: i64, span: Span, children: Vec<Value>| {
+        json!({
+            "name": name,
+            "detail": detail,
+            "kind": kind,
+            "range": range(span),
+            "selectionRange": name_range(text, span, name),
+            "children": children,
+        })
+    };
+
+    match stmt {
+        Stmt::Function {
+            name,
+            params,
+            return_type,
+            span,
+            ..
+        } => Some(document_symbol(
+            name,
+            formatter::function_header(name, params, return_type),
+            SYMBOL_FUNCTION,
+            *span,
+            Vec::new(),
+        )),
+        Stmt::Class { name, functions, span } => {
+            let methods = functions
+                .iter()
+                .filter_map(|function| match function {
+                    Stmt::Function {
+                        name,
+                        params,
+                        return_type,
+                        span,
+                        ..
+                    } => Some(document_symbol(
+                        name,
+                        formatter::function_header(name, params, return_type),
+                        SYMBOL_METHOD,
+                        *span,
+                        Vec::new(),
+                    )),
+                    _ => None,
+                })
+                .collect();
+
+            Some(document_symbol(name, format!("pookie {}()", name), SYMBOL_CLASS, *span, methods))
+        }
+        Stmt::Enum { name, variants, span } => {
+            let variants = variants
+                .iter()
+                .map(|variant| {
+                    document_symbol(
+                        &variant.name,
+                        variant_text(&variant.name, &variant.fields),
+                        SYMBOL_ENUM_MEMBER,
+                        variant.span,
+                        Vec::new(),
+                    )
+                })
+                .collect();
+
+            Some(document_symbol(name, format!("vibe {}", name), SYMBOL_ENUM, *span, variants))
+        }
+        Stmt::Constant { name, span, .. } => {
+            Some(document_symbol(name, format!("based {}", name), SYMBOL_CONSTANT, *span, Vec::new()))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URI: &str = "file:///game.skibidi";
+
+    const SCRIPT: &str = "\
+gyatt nerd
+
+pookie Zombie()
+    cookable __edge__(speed: number)
+        goat.speed is speed
+    slay
+
+    cookable run()
+        blud goat.speed
+    slay
+slay
+
+cookable spawn(count: number) -> number
+    blud count
+slay
+
+cookable fein()
+    z is mew Zombie(cook nerd.randInt(1, 3))
+    cook yap(cook spawn(2), cook z.run())
+slay
+";
+
+    fn open(text: &str) -> Server {
+        Server {
+            documents: HashMap::from([(URI.to_string(), text.to_string())]),
+            shutting_down: false,
+        }
+    }
+
+    fn at(line: usize, character: usize) -> Value {
+        json!({ "textDocument": { "uri": URI }, "position": { "line": line, "character": character } })
+    }
+
+    fn labels(items: Value) -> Vec<String> {
+        let mut labels: Vec<String> = items
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["label"].as_str().unwrap().to_string())
+            .collect();
+        labels.sort();
+
+        labels
+    }
+
+    #[test]
+    fn messages_are_framed() {
+        let mut input = io::Cursor::new("Content-Length: 13\r\n\r\n{\"id\": 1}    Content-Length: 2\r\n\r\n{}");
+
+        assert_eq!(read_message(&mut input).unwrap(), Some(json!({ "id": 1 })));
+        assert_eq!(read_message(&mut input).unwrap(), Some(json!({})));
+        assert_eq!(read_message(&mut input).unwrap(), None);
+
+        let mut input = io::Cursor::new("\r\n{}");
+        assert!(read_message(&mut input).is_err());
+    }
+
+    #[test]
+    fn diagnostics_are_zero_based() {
+        let diagnostics: Vec<Value> = parse("x is is 1\n").diagnostics.iter().map(lsp_diagnostic).collect();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["code"], "E0002");
+        assert_eq!(diagnostics[0]["severity"], 1);
+        assert_eq!(diagnostics[0]["range"], json!({
+            "start": { "line": 0, "character": 5 },
+            "end": { "line": 0, "character": 7 },
+        }));
+
+        // Lints only once the script parses
+        let parsed = parse("cookable fein()\n    blud 1\n    blud 2\nslay\n");
+        assert_eq!(parsed.diagnostics[0].code, Some("W0004"));
+    }
+
+    #[test]
+    fn hover_shows_the_signature() {
+        let server = open(SCRIPT);
+
+        let hover = server.hover(&at(18, 20)).unwrap();
+        assert_eq!(hover["contents"]["value"], "```skibidiscript\ncookable spawn(count: number) -> number\n```");
+        assert_eq!(hover["range"]["start"]["character"], 18);
+
+        let hover = server.hover(&at(17, 30)).unwrap();
+        assert!(hover["contents"]["value"].as_str().unwrap().contains("randInt"));
+    }
+
+    #[test]
+    fn definition_goes_to_the_name() {
+        let server = open(SCRIPT);
+
+        let definition = server.definition(&at(18, 20)).unwrap();
+        assert_eq!(definition["range"]["start"], json!({ "line": 12, "character": 9 }));
+
+        let definition = server.definition(&at(17, 14)).unwrap();
+        assert_eq!(definition["range"]["start"], json!({ "line": 2, "character": 7 }));
+    }
+
+    #[test]
+    fn completions() {
+        let server = open(&SCRIPT.replace("goat.speed\n    slay\nslay", "goat.\n    slay\nslay"));
+
+        assert_eq!(labels(server.completion(&at(8, 18)).unwrap()), vec!["__edge__", "run", "speed"]);
+        assert!(labels(server.completion(&at(17, 0)).unwrap()).contains(&"spawn".to_string()));
+    }
+
+    #[test]
+    fn symbols_and_formatting() {
+        let server = open("cookable fein()\n  x is 1\nslay\n");
+
+        let symbols = server.symbols(&at(0, 0)).unwrap();
+        assert_eq!(symbols[0]["name"], "fein");
+
+        let edits = server.formatting(&at(0, 0)).unwrap();
+        assert_eq!(edits[0]["newText"], "cookable fein()\n    x is 1\nslay\n");
+
+        assert_eq!(open("x is is\n").formatting(&at(0, 0)), None);
+    }
+}
@@ -1,79 +1,455 @@
 // main.rs
+mod cli;
+mod dap;
+mod debugger;
+mod diagnostic;
 mod error;
+mod explain;
+mod formatter;
+mod generator;
 mod interpreter;
 mod lexer;
 mod libs;
+mod lint;
+mod lsp;
 mod parser;
+mod range;
+mod repl;
+mod suggest;
+mod typecheck;
 
+use cli::{Command, Options};
+use diagnostic::Diagnostic;
 use interpreter::Interpreter;
-use lexer::Lexer;
-use parser::Parser;
+use lexer::{Comment, Lexer, Span, Token};
+use lint::LintKind;
+use parser::{Expr, Parser, Stmt};
+use serde::Serialize;
 use std::env;
 use std::fs;
+use std::io::{self, Write};
+use std::process;
+
+// Exit codes, a script can also blud its own from the entry function
+const EXIT_ERROR: i32 = 1; // The script has errors, or failed while running
+const EXIT_USAGE: i32 = 2; // The command line was wrong
 
 fn main() {
     let args: Vec<String> = env::args().collect();
 
-    // First check that we have a script
-    if args.len() < 2 {
-        println!("Usage: {} <script>", args[0]);
+    let options = match cli::parse_args(&args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, cli::usage(&args[0]));
+
+            process::exit(EXIT_USAGE);
+        }
+    };
+
+    if options.command == Command::Help {
+        println!("{}", cli::usage(&args[0]));
 
         return;
     }
 
-    // Read the file
-    let filename = &args[1];
-    let source = match fs::read_to_string(filename) {
+    let target = options.target.clone().unwrap_or_default();
+
+    if options.command == Command::Explain {
+        process::exit(explain(&target));
+    }
+
+    if options.command == Command::Lsp {
+        process::exit(lsp::run());
+    }
+
+    if options.command == Command::Dap {
+        process::exit(dap::run(&args[0]));
+    }
+
+    if options.command == Command::Repl && options.target.is_none() {
+        process::exit(repl::run(repl::Repl::new(&options)));
+    }
+
+    let source = match fs::read_to_string(&target) {
         Ok(source) => source,
         Err(e) => {
-            println!("error: Couldn't read file: {}", e);
+            eprintln!("error: couldn't read {}: {}", target, e);
 
-            return;
+            process::exit(EXIT_ERROR);
         }
     };
 
-    // Initialize the lexer and parser
-    let mut lexer = Lexer::new(&source);
+    let script = Script {
+        program: args[0].clone(),
+        filename: target,
+        source,
+        options,
+        errors: stderr,
+    };
 
-    let mut parser = match Parser::new(&mut lexer) {
-        Ok(parser) => parser,
-        Err(e) => {
-            println!("parser init error: {}", e);
+    let code = match script.options.command {
+        Command::Repl => {
+            let mut repl = repl::Repl::new(&script.options);
 
-            return;
+            match repl.load(&script.filename, &script.source) {
+                true => repl::run(repl),
+                false => EXIT_ERROR,
+            }
+        }
+        Command::Tokens => script.tokens(),
+        Command::Ast => script.ast(),
+        Command::Fmt => script.fmt(),
+        Command::Check => match script.parse().filter(|statements| script.check(statements)) {
+            Some(_) => 0,
+            None => EXIT_ERROR,
+        },
+        Command::Debug => {
+            let mut interpreter = Interpreter::new();
+            interpreter.hook = Some(Box::new(debugger::Terminal::new(&script.source)));
+
+            script.run(interpreter)
         }
+        _ => script.run(Interpreter::new()),
     };
 
-    let statements = match parser.parse() {
-        Ok(statements) => statements,
-        Err(e) => {
-            println!("parse_error: {}", e);
+    // exit doesn't flush, and a yap without a newline would get lost
+    let _ = io::stdout().flush();
+    process::exit(code);
+}
 
-            return;
+// skibidyscript explain E0001, lint names work too
+fn explain(code: &str) -> i32 {
+    let code = LintKind::from_name(code).map_or(code, |kind| kind.code());
+
+    match explain::explanation(code) {
+        Some(text) => {
+            println!("{}", text);
+
+            0
         }
-    };
+        None => {
+            eprintln!("error: there's no error or lint with the code {}", code);
 
-    println!("statements: {:#?}", statements);
+            EXIT_ERROR
+        }
+    }
+}
 
-    let mut interpreter = Interpreter::new();
-    match interpreter.interpret(statements) {
-        Ok(_) => {}
-        Err(e) => {
-            println!("interpreter: {}", e);
+fn stderr(text: &str) {
+    eprint!("{}", text);
+}
+
+// The script a command works on. Errors are reported as they're found, a None or false
+// back means there was one and the command should stop
+struct Script {
+    program: String,
+    filename: String,
+    source: String,
+    options: Options,
+    errors: fn(&str), // Where errors go, stderr unless the debug adapter sends them to the editor
+}
 
+impl Script {
+    // Errors show the line they happened on, with carets under the spot
+    fn report(&self, diagnostic: Diagnostic) {
+        if self.options.quiet && diagnostic.severity == diagnostic::Severity::Warning {
             return;
         }
-    };
 
-    // Run the mew function, which is the main function
-    match interpreter.execute_user_function("fein".to_string(), vec![]) {
-        Ok(_) => {}
-        Err(e) => {
-            println!("interpreter: {}", e);
+        (self.errors)(&diagnostic.render(&self.source, &self.filename, diagnostic::use_color(&io::stderr())));
+    }
 
-            return;
+    fn hint(&self, code: &str) {
+        if !self.options.quiet {
+            (self.errors)(&format!("for more about this error, run `{} explain {}`\n", self.program, code));
+        }
+    }
+
+    fn parse(&self) -> Option<Vec<Stmt>> {
+        self.parse_with_comments().map(|(statements, _)| statements)
+    }
+
+    // The comments are only wanted by fmt, everything else goes without them
+    fn parse_with_comments(&self) -> Option<(Vec<Stmt>, Vec<Comment>)> {
+        let mut lexer = Lexer::new(&self.source);
+
+        let mut parser = match Parser::new(&mut lexer) {
+            Ok(parser) => parser,
+            Err(e) => {
+                self.report(Diagnostic::from(&e));
+                self.hint(e.code());
+
+                return None;
+            }
+        };
+
+        // Every syntax error gets reported, not just the first one
+        let (statements, parse_errors) = parser.parse();
+        if !parse_errors.is_empty() {
+            for e in &parse_errors {
+                self.report(Diagnostic::from(e));
+            }
+            self.hint(parse_errors[0].code());
+
+            return None;
+        }
+
+        Some((statements, lexer.comments))
+    }
+
+    // Lints, which are only warnings, then the type checker
+    fn check(&self, statements: &[Stmt]) -> bool {
+        let entry = self.options.entry.as_deref().unwrap_or("fein");
+
+        for lint in lint::check(statements, entry) {
+            if !self.options.allowed.contains(&lint.kind) {
+                self.report(Diagnostic::from(&lint));
+            }
+        }
+
+        // Catch type mistakes before anything runs
+        let type_errors = typecheck::check(statements);
+        if !type_errors.is_empty() {
+            for e in &type_errors {
+                self.report(Diagnostic::from(e));
+            }
+            self.hint(type_errors[0].code());
+
+            return false;
+        }
+
+        true
+    }
+
+    fn tokens(&self) -> i32 {
+        #[derive(Serialize)]
+        struct SpannedToken {
+            token: Token,
+            span: Span,
         }
-    };
 
-    // println!("{:#?}", interpreter.instances);
+        let mut lexer = Lexer::new(&self.source);
+        let mut tokens = Vec::new();
+
+        loop {
+            match lexer.next_token() {
+                Ok((Token::EOF, _)) => break,
+                Ok((token, span)) => tokens.push(SpannedToken { token, span }),
+                Err(e) => {
+                    self.report(Diagnostic::from(&e));
+                    self.hint(e.code());
+
+                    return EXIT_ERROR;
+                }
+            }
+        }
+
+        if self.options.json {
+            println!("{}", serde_json::to_string_pretty(&tokens).unwrap_or_default());
+        } else {
+            for SpannedToken { token, span } in tokens {
+                println!("{}:{}\t{:?}", span.line, span.column, token);
+            }
+        }
+
+        0
+    }
+
+    fn ast(&self) -> i32 {
+        let statements = match self.parse() {
+            Some(statements) => statements,
+            None => return EXIT_ERROR,
+        };
+
+        if self.options.json {
+            println!("{}", serde_json::to_string_pretty(&statements).unwrap_or_default());
+        } else {
+            println!("{:#?}", statements);
+        }
+
+        0
+    }
+
+    // Rewrites the script in place, or with --check only says whether it would
+    fn fmt(&self) -> i32 {
+        let (statements, comments) = match self.parse_with_comments() {
+            Some(parsed) => parsed,
+            None => return EXIT_ERROR,
+        };
+
+        let formatted = formatter::format(&self.source, &statements, &comments);
+        if formatted == self.source {
+            return 0;
+        }
+
+        if self.options.check {
+            // The first line that's different, so it's easy to find
+            let line = self
+                .source
+                .lines()
+                .zip(formatted.lines())
+                .position(|(old, new)| old != new)
+                .unwrap_or_else(|| self.source.lines().count().min(formatted.lines().count()))
+                + 1;
+
+            eprintln!("{} isn't formatted, starting at line {}", self.filename, line);
+
+            return EXIT_ERROR;
+        }
+
+        if let Err(e) = fs::write(&self.filename, formatted) {
+            eprintln!("error: couldn't write {}: {}", self.filename, e);
+
+            return EXIT_ERROR;
+        }
+
+        0
+    }
+
+    // The interpreter can come with a hook or somewhere else for yap to go, for the debuggers
+    fn run(&self, mut interpreter: Interpreter) -> i32 {
+        let statements = match self.parse() {
+            Some(statements) if self.check(&statements) => statements,
+            _ => return EXIT_ERROR,
+        };
+
+        interpreter.strict = self.options.strict;
+
+        if let Err(e) = interpreter.interpret(statements) {
+            self.report(Diagnostic::from(&e));
+            self.hint(e.code());
+
+            return EXIT_ERROR;
+        }
+
+        // Run the entry function, fein unless --entry says otherwise. A script that's only
+        // top level statements doesn't need one
+        let entry_name = self.options.entry.clone().unwrap_or_else(|| "fein".to_string());
+        let function = match interpreter.functions.get(&entry_name).cloned() {
+            Some(function) => function,
+            None if self.options.entry.is_none() => return 0,
+            None => {
+                (self.errors)(&format!("error: entry function {} not found\n", entry_name));
+
+                return EXIT_ERROR;
+            }
+        };
+
+        // fein() doesn't care about the arguments, fein(args) gets them as a list of strings
+        let entry_args = match function.params.len() {
+            0 => vec![],
+            1 => vec![Expr::List(
+                self.options
+                    .script_args
+                    .iter()
+                    .map(|arg| Expr::StringLiteral(arg.clone()))
+                    .collect(),
+            )],
+            _ => {
+                (self.errors)(&format!(
+                    "error: entry function {} should take no parameters or just one for the arguments\n",
+                    entry_name
+                ));
+
+                return EXIT_ERROR;
+            }
+        };
+
+        // Nothing cooked the entry function, so it has no call site in a traceback
+        interpreter.span = Span::default();
+
+        match interpreter.execute_user_function(entry_name.clone(), entry_args) {
+            // blud with a number from the entry function is the exit code
+            Ok(Expr::Number(code @ 0..=255)) => code as i32,
+            // The OS only keeps the low byte, so 256 would look like success
+            Ok(code @ (Expr::Number(_) | Expr::BigNumber(_))) => {
+                (self.errors)(&format!(
+                    "error: {} gave back {}, but an exit code has to be between 0 and 255\n",
+                    entry_name,
+                    interpreter.format_value(code, false).unwrap_or_default()
+                ));
+
+                EXIT_ERROR
+            }
+            Ok(_) => 0,
+            Err(e) => {
+                self.report(Diagnostic::from(&e));
+                self.hint(e.code());
+
+                EXIT_ERROR
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // args is the command line after the program name
+    fn exit_code(source: &str, args: &[&str]) -> i32 {
+        let args: Vec<String> = ["skibidyscript"].iter().chain(args).map(|arg| arg.to_string()).collect();
+        let options = cli::parse_args(&args).unwrap();
+        let script = Script {
+            program: args[0].clone(),
+            filename: options.target.clone().unwrap_or_default(),
+            source: source.to_string(),
+            options,
+            errors: |_| {},
+        };
+
+        let mut interpreter = Interpreter::new();
+        interpreter.output = Box::new(io::sink());
+
+        script.run(interpreter)
+    }
+
+    #[test]
+    fn fein_gives_the_exit_code() {
+        assert_eq!(exit_code("cookable fein()\n    blud 7\nslay\n", &["test.skibidi"]), 7);
+        assert_eq!(exit_code("cookable fein()\n    blud 255\nslay\n", &["test.skibidi"]), 255);
+        assert_eq!(exit_code("x is 1\n", &["test.skibidi"]), 0);
+    }
+
+    #[test]
+    fn exit_codes_out_of_range_are_errors() {
+        for code in ["256", "4294967296", "-1", "99999999999999999999"] {
+            let source = format!("cookable fein()\n    blud {}\nslay\n", code);
+
+            assert_eq!(exit_code(&source, &["test.skibidi"]), EXIT_ERROR, "{}", code);
+        }
+    }
+
+    #[test]
+    fn fein_gets_the_script_args() {
+        // Flags after the script are the script's, not ours
+        let source = "\
+cookable fein(args)
+    total is 0
+    goon (arg in args) eat
+        total is total * 10 + cook thicc(arg)
+    slay
+    blud total
+slay
+";
+
+        assert_eq!(exit_code(source, &["test.skibidi", "a", "--bc"]), 14);
+        assert_eq!(exit_code("cookable fein(args)\n    blud cook thicc(args)\nslay\n", &["test.skibidi"]), 0);
+    }
+
+    #[test]
+    fn entry_picks_another_function() {
+        let source = "cookable fein()\n    blud 1\nslay\n\ncookable other()\n    blud 2\nslay\n";
+
+        assert_eq!(exit_code(source, &["--entry", "other", "test.skibidi"]), 2);
+        assert_eq!(exit_code(source, &["--entry", "missing", "test.skibidi"]), EXIT_ERROR);
+        assert_eq!(exit_code("cookable fein(a, b)\nslay\n", &["test.skibidi"]), EXIT_ERROR);
+    }
+
+    #[test]
+    fn explain_takes_codes_and_lint_names() {
+        assert_eq!(explain("E0007"), 0);
+        assert_eq!(explain("unused_variable"), 0);
+        assert_eq!(explain("not_a_lint"), EXIT_ERROR);
+    }
 }
@@ -2,15 +2,22 @@
 
 // todo: (refactor) use a shared function for running function statements and checking control flow.
 
+use std::{collections::HashMap, fmt};
+
+use num_bigint::BigInt;
+use serde::Serialize;
+
 use crate::{
     error,
-    lexer::{Lexer, Token},
+    lexer::{self, Lexer, Span, Token},
 };
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize)]
 pub enum Expr {
     Ident(String),
     Number(i64),
+    // Only for numbers past what an i64 holds, see Expr::whole_number
+    BigNumber(#[serde(serialize_with = "lexer::serialize_big")] BigInt),
     StringLiteral(String),
     Boolean(bool),
     // None,
@@ -36,82 +43,303 @@ pub enum Expr {
     Instance {
         class_name: String,
         instance_id: String,
+    },
+    EnumVariant {
+        enum_name: String,
+        variant: String,
+        payload: Vec<Expr>,
+    },
+    Generator {
+        function: String,
+        id: usize,
+    },
+    Not(Box<Expr>),
+    // Where the parser found the expression inside, gone once it's evaluated
+    Spanned {
+        expr: Box<Expr>,
+        span: Span,
+    },
+    // What a coroutine yeets to pause for a while
+    Wait(WaitFor),
+    // Lazy, nothing is allocated until it is turned into a list
+    Range {
+        start: Box<Expr>,
+        end: Box<Expr>,
+        step: Option<Box<Expr>>,
+        inclusive: bool,
+    },
+}
+
+impl Expr {
+    pub fn unspanned(&self) -> &Expr {
+        match self {
+            Expr::Spanned { expr, .. } => expr.unspanned(),
+            expr => expr,
+        }
+    }
+
+    // A number that fits in an i64 is always a plain Number, so the two never have to be compared
+    pub fn whole_number(value: BigInt) -> Expr {
+        match i64::try_from(&value) {
+            Ok(value) => Expr::Number(value),
+            Err(_) => Expr::BigNumber(value),
+        }
+    }
+
+    // What kind of value this is, in the words a script would use
+    pub fn type_name(&self) -> String {
+        match self.unspanned() {
+            Expr::Number(_) | Expr::BigNumber(_) => "number".to_string(),
+            Expr::StringLiteral(_) => "string".to_string(),
+            Expr::Boolean(_) => "bool".to_string(),
+            Expr::List(_) => "list".to_string(),
+            Expr::Range { .. } => "range".to_string(),
+            Expr::Generator { .. } => "generator".to_string(),
+            Expr::Wait(_) => "wait".to_string(),
+            Expr::Instance { class_name, .. } => class_name.clone(),
+            Expr::EnumVariant { enum_name, .. } => enum_name.clone(),
+            Expr::BinOp { left, op, right } => {
+                format!("{} {} {}", left.type_name(), op, right.type_name())
+            }
+            _ => "expression".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub enum WaitFor {
+    Frames(i64),
+    Millis(i64),
+}
+
+// Optional type annotations, only used by the typecheck pass
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub enum Type {
+    Number,
+    String,
+    Boolean,
+    List,
+    Range,
+    Generator,
+    Any,
+    Class(String),
+}
+
+// Anything else has to be a pookie or a vibe, the type checker makes sure it is
+pub const BUILTIN_TYPES: &[&str] = &["number", "string", "bool", "list", "range", "generator", "any"];
+
+impl Type {
+    pub fn from_name(name: &str) -> Type {
+        match name {
+            "number" => Type::Number,
+            "string" => Type::String,
+            "bool" => Type::Boolean,
+            "list" => Type::List,
+            "range" => Type::Range,
+            "generator" => Type::Generator,
+            "any" => Type::Any,
+            _ => Type::Class(name.to_string()),
+        }
     }
 }
 
-#[derive(Clone, Debug)]
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Number => write!(f, "number"),
+            Type::String => write!(f, "string"),
+            Type::Boolean => write!(f, "bool"),
+            Type::List => write!(f, "list"),
+            Type::Range => write!(f, "range"),
+            Type::Generator => write!(f, "generator"),
+            Type::Any => write!(f, "any"),
+            Type::Class(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Param {
+    pub name: String,
+    pub type_annotation: Option<Type>,
+}
+
+impl fmt::Display for Param {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.type_annotation {
+            Some(type_annotation) => write!(f, "{}: {}", self.name, type_annotation),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct EnumVariant {
+    pub name: String,
+    pub fields: Vec<String>,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub enum Pattern {
+    // State.GameOver(score), binds the payload
+    Variant {
+        enum_name: String,
+        variant: String,
+        bindings: Vec<String>,
+    },
+    // Anything else is evaluated and compared with rizz
+    Value(Expr),
+    Wildcard,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub body: Vec<Stmt>,
+    pub span: Span, // Just the pattern
+}
+
+#[derive(Clone, Debug, Serialize)]
 pub enum Stmt {
     Class {
         name: String,
         functions: Vec<Stmt>,
-        line: usize,
+        span: Span,
     },
     Function {
         name: String,
+        params: Vec<Param>,
+        return_type: Option<Type>,
         body: Vec<Stmt>,
-        line: usize,
+        span: Span,
     },
     VariableAssign {
         name: String,
         object: Option<Box<Expr>>,
+        type_annotation: Option<Type>,
+        value: Expr,
+        span: Span,
+    },
+    Constant {
+        name: String,
+        type_annotation: Option<Type>,
         value: Expr,
-        line: usize,
+        span: Span,
     },
     While {
         condition: Expr,
         body: Vec<Stmt>,
-        line: usize,
+        span: Span,
     },
     If {
         condition: Expr,
         then_branch: Vec<Stmt>,
         else_branch: Option<Vec<Stmt>>,
-        line: usize,
+        span: Span,
     },
     Expression {
         value: Expr,
-        line: usize,
+        span: Span,
     },
     Return {
         value: Expr,
-        line: usize,
+        span: Span,
+    },
+    Yield {
+        value: Expr,
+        span: Span,
     },
     Import {
         library: String,
-        line: usize,
+        span: Span,
     },
     Continue {
-        line: usize,
+        span: Span,
+    },
+    DestructureAssign {
+        names: Vec<String>,
+        value: Expr,
+        span: Span,
     },
     ForLoop {
-        iterator: String,
+        iterators: Vec<String>,
         collection: Expr,
         body: Vec<Stmt>,
-        line: usize,
+        span: Span,
     },
+    Enum {
+        name: String,
+        variants: Vec<EnumVariant>,
+        span: Span,
+    },
+    Match {
+        subject: Expr,
+        arms: Vec<MatchArm>,
+        span: Span,
+    },
+}
+
+impl Stmt {
+    pub fn span(&self) -> Span {
+        match self {
+            Stmt::Class { span, .. }
+            | Stmt::Function { span, .. }
+            | Stmt::VariableAssign { span, .. }
+            | Stmt::Constant { span, .. }
+            | Stmt::While { span, .. }
+            | Stmt::If { span, .. }
+            | Stmt::Expression { span, .. }
+            | Stmt::Return { span, .. }
+            | Stmt::Yield { span, .. }
+            | Stmt::Import { span, .. }
+            | Stmt::Continue { span }
+            | Stmt::DestructureAssign { span, .. }
+            | Stmt::ForLoop { span, .. }
+            | Stmt::Enum { span, .. }
+            | Stmt::Match { span, .. } => *span,
+        }
+    }
 }
 
 pub struct Parser<'a> {
     lexer: &'a mut Lexer,
     current_token: Token,
+    current_span: Span,
+    previous_span: Span, // The token before the current one, where things end
+    errors: Vec<error::ParseError>, // Everything that went wrong, parsing goes on after an error
+    constants: HashMap<String, Span>, // Constant name, where it was declared
 }
 
 impl<'a> Parser<'a> {
     pub fn new(lexer: &'a mut Lexer) -> Result<Self, error::ParseError> {
-        let current_token = lexer.next_token()?;
+        let (current_token, current_span) = lexer.next_token()?;
 
         Ok(Parser {
             lexer,
             current_token,
+            current_span,
+            previous_span: current_span,
+            errors: Vec::new(),
+            constants: HashMap::new(),
         })
     }
 
     fn next_token(&mut self) -> Result<(), error::ParseError> {
-        self.current_token = self.lexer.next_token()?;
+        let (token, span) = self.lexer.next_token()?;
+
+        self.previous_span = self.current_span;
+        self.current_token = token;
+        self.current_span = span;
 
         Ok(())
     }
 
+    // From where something started to the end of the last token it used
+    fn span_from(&self, start: Span) -> Span {
+        start.to(self.previous_span)
+    }
+
     fn expect_token(&mut self, expected: Token) -> Result<(), error::ParseError> {
         if self.current_token == expected {
             self.next_token()?;
@@ -121,23 +349,82 @@ impl<'a> Parser<'a> {
             Err(error::ParseError::UnexpectedToken {
                 expected: expected.clone(),
                 found: self.current_token.clone(),
-                line: self.lexer.line,
+                span: self.current_span,
             })
         }
     }
 
-    pub fn parse(&mut self) -> Result<Vec<Stmt>, error::ParseError> {
+    // Parses the whole script, carrying on past errors so they're all reported in one go.
+    // The statements are whatever could be made sense of, so they're only partial if there are errors
+    pub fn parse(&mut self) -> (Vec<Stmt>, Vec<error::ParseError>) {
+        let statements = self.parse_block(&[]);
+
+        (statements, std::mem::take(&mut self.errors))
+    }
+
+    // Statements until one of the terminators (not consumed), or the end of the file
+    fn parse_block(&mut self, terminators: &[Token]) -> Vec<Stmt> {
         let mut statements = Vec::new();
-        while self.current_token != Token::EOF {
-            // ...debug
 
-            let stmt = self.parse_statement()?;
-            statements.push(stmt);
+        while !terminators.contains(&self.current_token) && self.current_token != Token::EOF {
+            let start = self.current_span;
 
-            // println!("current token: {:#?}", statements);
+            match self.parse_statement() {
+                Ok(stmt) => statements.push(stmt),
+                Err(e) => self.recover(e, start),
+            }
+        }
+
+        statements
+    }
+
+    // Panic mode: remember the error and skip ahead to something that starts a statement
+    // or ends a block, then carry on from there. A name first on its line starts one too,
+    // most statements are assignments
+    fn recover(&mut self, error: error::ParseError, start: Span) {
+        self.errors.push(error);
+
+        // Always skip at least one token, or an error right at a keyword would loop forever
+        if self.current_span == start {
+            self.skip_token();
         }
 
-        Ok(statements)
+        loop {
+            let keyword = matches!(
+                self.current_token,
+                Token::Cookable
+                    | Token::Pookie
+                    | Token::Suspect
+                    | Token::Cap
+                    | Token::Skibidi
+                    | Token::Goon
+                    | Token::Cook
+                    | Token::Blud
+                    | Token::Yeet
+                    | Token::Ghost
+                    | Token::Based
+                    | Token::Vibe
+                    | Token::VibeCheck
+                    | Token::Gyatt
+                    | Token::Slay
+                    | Token::EOF
+            );
+            let line_start = matches!(self.current_token, Token::Ident(_))
+                && self.current_span.line > self.previous_span.end_line;
+
+            if keyword || line_start {
+                return;
+            }
+
+            self.skip_token();
+        }
+    }
+
+    // The lexer has already moved past a bad character when it errors, so just note it
+    fn skip_token(&mut self) {
+        if let Err(e) = self.next_token() {
+            self.errors.push(e);
+        }
     }
 
     fn parse_statement(&mut self) -> Result<Stmt, error::ParseError> {
@@ -151,22 +438,28 @@ impl<'a> Parser<'a> {
             Token::Goon => self.parse_for_loop(),
             Token::Suspect => self.parse_if(),
             Token::Blud => self.parse_return(),
+            Token::Yeet => self.parse_yield(),
             Token::Ghost => self.parse_continue(),
+            Token::Based => self.parse_constant(),
+            Token::Vibe => self.parse_enum(),
+            Token::VibeCheck => self.parse_match(),
             _ => Err(error::ParseError::UnknownUnexpectedToken {
                 found: self.current_token.clone(),
-                line: self.lexer.line,
+                span: self.current_span,
             }),
         }
     }
 
     fn parse_class(&mut self) -> Result<Stmt, error::ParseError> {
+        let start = self.current_span;
+
         self.expect_token(Token::Pookie)?;
 
         let name = if let Token::Ident(ident) = &self.current_token {
             ident.clone()
         } else {
             return Err(error::ParseError::GeneralError {
-                line: self.lexer.line,
+                span: self.current_span,
                 message: "Expected class name".to_string(),
             });
         };
@@ -176,47 +469,202 @@ impl<'a> Parser<'a> {
         self.expect_token(Token::RightParen)?;
 
         let mut functions = Vec::new();
-        let mut has_init = false;
 
         while self.current_token != Token::Slay && self.current_token != Token::EOF {
-            let method = self.parse_function()?;
-            if let Stmt::Function {
-                name: ref method_name,
-                ..
-            } = method
-            {
-                if method_name == "__edge__" {
-                    has_init = true;
+            let start = self.current_span;
+            let method = match self.parse_function() {
+                Ok(method) => method,
+                Err(e) => {
+                    self.recover(e, start);
+                    continue;
                 }
-            }
+            };
+
             functions.push(method);
         }
 
-        if !has_init {
+        // A missing __edge__ is left to the missing_edge lint, the pookie only breaks once it's mewed
+
+        self.expect_token(Token::Slay)?;
+
+        Ok(Stmt::Class {
+            name,
+            functions,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_enum(&mut self) -> Result<Stmt, error::ParseError> {
+        let start = self.current_span;
+
+        self.expect_token(Token::Vibe)?;
+
+        let name = if let Token::Ident(ident) = &self.current_token {
+            ident.clone()
+        } else {
             return Err(error::ParseError::GeneralError {
-                line: self.lexer.line,
-                message: "Class must have an __edge__ method".to_string(),
+                span: self.current_span,
+                message: "Expected vibe name".to_string(),
             });
+        };
+
+        self.next_token()?;
+
+        let mut variants = Vec::new();
+
+        while self.current_token != Token::Slay && self.current_token != Token::EOF {
+            let variant_start = self.current_span;
+            let variant_name = if let Token::Ident(ident) = &self.current_token {
+                ident.clone()
+            } else {
+                return Err(error::ParseError::GeneralError {
+                    span: self.current_span,
+                    message: format!("Expected variant name, found `{}`", self.current_token),
+                });
+            };
+
+            self.next_token()?;
+
+            // Payload fields, GameOver(score)
+            let mut fields = Vec::new();
+            if self.current_token == Token::LeftParen {
+                self.next_token()?;
+
+                while let Token::Ident(field) = &self.current_token {
+                    fields.push(field.clone());
+                    self.next_token()?;
+
+                    if self.current_token == Token::Comma {
+                        self.next_token()?;
+                    } else {
+                        break;
+                    }
+                }
+
+                self.expect_token(Token::RightParen)?;
+            }
+
+            if variants.iter().any(|variant: &EnumVariant| variant.name == variant_name) {
+                return Err(error::ParseError::GeneralError {
+                    span: variant_start, // Just the repeated name
+                    message: format!("Variant {} is already in vibe {}", variant_name, name),
+                });
+            }
+
+            variants.push(EnumVariant {
+                name: variant_name,
+                fields,
+                span: self.span_from(variant_start),
+            });
+
+            // Commas between variants are optional
+            if self.current_token == Token::Comma {
+                self.next_token()?;
+            }
         }
 
         self.expect_token(Token::Slay)?;
 
-        Ok(Stmt::Class {
+        Ok(Stmt::Enum {
             name,
-            functions,
-            line: self.lexer.line,
+            variants,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_match(&mut self) -> Result<Stmt, error::ParseError> {
+        let start = self.current_span;
+
+        self.expect_token(Token::VibeCheck)?;
+        self.expect_token(Token::LeftParen)?;
+
+        let subject = self.parse_expression()?;
+
+        self.expect_token(Token::RightParen)?;
+        self.expect_token(Token::Eat)?;
+
+        let mut arms = Vec::new();
+
+        while self.current_token != Token::Slay && self.current_token != Token::EOF {
+            let arm_start = self.current_span;
+            let pattern = self.parse_pattern()?;
+            let span = self.span_from(arm_start);
+
+            self.expect_token(Token::Eat)?;
+
+            let body = self.parse_block(&[Token::Slay]);
+
+            self.expect_token(Token::Slay)?;
+
+            arms.push(MatchArm { pattern, body, span });
+        }
+
+        self.expect_token(Token::Slay)?;
+
+        Ok(Stmt::Match {
+            subject,
+            arms,
+            span: self.span_from(start),
         })
     }
 
+    fn parse_pattern(&mut self) -> Result<Pattern, error::ParseError> {
+        if self.current_token == Token::Ident("_".to_string()) {
+            self.next_token()?;
+
+            return Ok(Pattern::Wildcard);
+        }
+
+        let start = self.current_span;
+        let expr = self.parse_expression()?;
+
+        // State.GameOver(score) binds, anything else is compared as a value
+        if let Expr::FunctionCall {
+            name,
+            object: Some(object),
+            args,
+        } = expr.unspanned()
+        {
+            if let Expr::Ident(enum_name) = object.as_ref() {
+                let bindings = args
+                    .iter()
+                    .map(|arg| match arg.unspanned() {
+                        Expr::Ident(binding) => Some(binding.clone()),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<String>>>();
+
+                if let Some(bindings) = bindings {
+                    // Binding a based name would overwrite it
+                    for binding in &bindings {
+                        if let Err(e) = self.check_not_constant(binding, self.span_from(start)) {
+                            self.errors.push(e);
+                        }
+                    }
+
+                    return Ok(Pattern::Variant {
+                        enum_name: enum_name.clone(),
+                        variant: name.clone(),
+                        bindings,
+                    });
+                }
+            }
+        }
+
+        Ok(Pattern::Value(expr))
+    }
+
     fn parse_function(&mut self) -> Result<Stmt, error::ParseError> {
+        let start = self.current_span;
+
         self.expect_token(Token::Cookable)?;
         let name = if let Token::Ident(ident) = &self.current_token {
             Ok(ident.clone())
         } else {
             Err(error::ParseError::GeneralError {
-                line: self.lexer.line,
+                span: self.current_span,
                 message: format!(
-                    "found token: {:?}, expected function name",
+                    "found `{}`, expected function name",
                     self.current_token.clone()
                 ),
             })
@@ -224,23 +672,87 @@ impl<'a> Parser<'a> {
 
         self.next_token()?;
         self.expect_token(Token::LeftParen)?;
-        self.expect_token(Token::RightParen)?;
 
-        let mut body = Vec::new();
-        while self.current_token != Token::Slay && self.current_token != Token::EOF {
-            body.push(self.parse_statement()?);
+        let mut params = Vec::new();
+        while self.current_token != Token::RightParen {
+            let param_name = if let Token::Ident(ident) = &self.current_token {
+                ident.clone()
+            } else {
+                return Err(error::ParseError::GeneralError {
+                    span: self.current_span,
+                    message: format!(
+                        "found `{}`, expected parameter name",
+                        self.current_token.clone()
+                    ),
+                });
+            };
+
+            self.next_token()?;
+
+            params.push(Param {
+                name: param_name,
+                type_annotation: self.parse_type_annotation()?,
+            });
+
+            if self.current_token == Token::Comma {
+                self.next_token()?;
+            } else {
+                break;
+            }
         }
 
+        self.expect_token(Token::RightParen)?;
+
+        // Optional return type, cookable add(a: number, b: number) -> number
+        let return_type = if self.current_token == Token::Arrow {
+            self.next_token()?;
+            Some(self.parse_type()?)
+        } else {
+            None
+        };
+
+        let body = self.parse_block(&[Token::Slay]);
+
         self.expect_token(Token::Slay)?;
 
         Ok(Stmt::Function {
             name,
+            params,
+            return_type,
             body,
-            line: self.lexer.line,
+            span: self.span_from(start),
         })
     }
 
+    // Parses `: type` if there is one
+    fn parse_type_annotation(&mut self) -> Result<Option<Type>, error::ParseError> {
+        if self.current_token != Token::Colon {
+            return Ok(None);
+        }
+
+        self.next_token()?;
+
+        Ok(Some(self.parse_type()?))
+    }
+
+    fn parse_type(&mut self) -> Result<Type, error::ParseError> {
+        let type_name = if let Token::Ident(ident) = &self.current_token {
+            ident.clone()
+        } else {
+            return Err(error::ParseError::GeneralError {
+                span: self.current_span,
+                message: format!("Expected a type name, found `{}`", self.current_token),
+            });
+        };
+
+        self.next_token()?;
+
+        Ok(Type::from_name(&type_name))
+    }
+
     fn parse_variable_assign_or_expression(&mut self) -> Result<Stmt, error::ParseError> {
+        let start = self.current_span;
+
         let (object, name) = match &self.current_token {
             Token::Ident(ident) => {
                 let name = ident.clone();
@@ -256,7 +768,7 @@ impl<'a> Parser<'a> {
                         ident.clone()
                     } else {
                         return Err(error::ParseError::GeneralError {
-                            line: self.lexer.line,
+                            span: self.current_span,
                             message: "Expected identifier after 'self.'".to_string(),
                         });
                     };
@@ -273,7 +785,7 @@ impl<'a> Parser<'a> {
                     //     };
                     // } else {
                     //     return Err(error::ParseError::UnexpectedToken {
-                    //         line: self.lexer.line,
+                    //         span: self.current_span,
                     //         expected: Token::Ident("variable name after .".into()),
                     //         found: self.current_token.clone(),
                     //     });
@@ -287,7 +799,31 @@ impl<'a> Parser<'a> {
             _ => return Err(error::ParseError::Other("Expected identifier".into())),
         };
 
+        // x, y is cook direction()
+        if object.is_none() && self.current_token == Token::Comma {
+            return self.parse_destructure_assign(name, start);
+        }
+
+        // score: number is 0
+        let type_annotation = if object.is_none() {
+            self.parse_type_annotation()?
+        } else {
+            None
+        };
+
+        if type_annotation.is_some() && self.current_token != Token::Is {
+            return Err(error::ParseError::UnexpectedToken {
+                expected: Token::Is,
+                found: self.current_token.clone(),
+                span: self.current_span,
+            });
+        }
+
         if self.current_token == Token::Is {
+            if object.is_none() {
+                self.check_not_constant(&name, start)?;
+            }
+
             self.next_token()?;
 
             // 1HERE: FAULT
@@ -297,14 +833,16 @@ impl<'a> Parser<'a> {
                 Some(obj) => Ok(Stmt::VariableAssign {
                     name,
                     object: Some(obj),
+                    type_annotation: None,
                     value,
-                    line: self.lexer.line,
+                    span: self.span_from(start),
                 }),
                 None => Ok(Stmt::VariableAssign {
                     name,
                     object: None,
+                    type_annotation,
                     value,
-                    line: self.lexer.line,
+                    span: self.span_from(start),
                 }),
             }
         } else {
@@ -312,14 +850,144 @@ impl<'a> Parser<'a> {
 
             Ok(Stmt::Expression {
                 value: expr,
-                line: self.lexer.line,
+                span: self.span_from(start),
             })
         }
     }
 
+    fn parse_destructure_assign(&mut self, first: String, start: Span) -> Result<Stmt, error::ParseError> {
+        let mut names = vec![first];
+
+        while self.current_token == Token::Comma {
+            self.next_token()?;
+
+            if let Token::Ident(ident) = &self.current_token {
+                names.push(ident.clone());
+            } else {
+                return Err(error::ParseError::GeneralError {
+                    span: self.current_span,
+                    message: format!(
+                        "Expected a variable name after ',', found `{}`",
+                        self.current_token
+                    ),
+                });
+            }
+
+            self.next_token()?;
+        }
+
+        for name in &names {
+            self.check_not_constant(name, start)?;
+        }
+
+        self.expect_token(Token::Is)?;
+
+        let value = self.parse_expression()?;
+
+        Ok(Stmt::DestructureAssign {
+            names,
+            value,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_constant(&mut self) -> Result<Stmt, error::ParseError> {
+        let start = self.current_span;
+
+        self.expect_token(Token::Based)?;
+
+        let name = if let Token::Ident(ident) = &self.current_token {
+            ident.clone()
+        } else {
+            return Err(error::ParseError::GeneralError {
+                span: self.current_span,
+                message: format!(
+                    "Expected a constant name after 'based', found `{}`",
+                    self.current_token
+                ),
+            });
+        };
+
+        self.check_not_constant(&name, self.current_span)?;
+
+        self.next_token()?;
+
+        let type_annotation = self.parse_type_annotation()?;
+
+        self.expect_token(Token::Is)?;
+
+        let value = self.parse_expression()?;
+
+        let span = self.span_from(start);
+        self.constants.insert(name.clone(), span);
+
+        Ok(Stmt::Constant {
+            name,
+            type_annotation,
+            value,
+            span,
+        })
+    }
+
+    // Catches the obvious reassignments, the interpreter catches the rest at runtime
+    fn check_not_constant(&self, name: &str, span: Span) -> Result<(), error::ParseError> {
+        if let Some(declared_at) = self.constants.get(name) {
+            return Err(error::ParseError::ConstantReassign {
+                name: name.to_string(),
+                declared_at: *declared_at,
+                span,
+            });
+        }
+
+        Ok(())
+    }
+
+    // Input that's nothing but one expression, for the repl to yap back
+    pub fn parse_lone_expression(&mut self) -> Result<Expr, error::ParseError> {
+        let expr = self.parse_expression()?;
+
+        if self.current_token != Token::EOF {
+            return Err(error::ParseError::UnknownUnexpectedToken {
+                found: self.current_token.clone(),
+                span: self.current_span,
+            });
+        }
+
+        Ok(expr)
+    }
+
     fn parse_expression(&mut self) -> Result<Expr, error::ParseError> {
-        self.parse_expression_with_precedence(0)
+        let start = self.current_span;
+        let expr = self.parse_expression_with_precedence(0)?;
         // self.parse_primary()
+
+        // Ranges bind the loosest, so 0..count + 1 works
+        let inclusive = match self.current_token {
+            Token::DotDot => false,
+            Token::DotDotEq => true,
+            _ => return Ok(expr),
+        };
+
+        self.next_token()?;
+
+        let end = self.parse_expression_with_precedence(0)?;
+
+        // 'by' is only special right after a range, so it can still be used as a name
+        let step = if self.current_token == Token::Ident("by".to_string()) {
+            self.next_token()?;
+            Some(Box::new(self.parse_expression_with_precedence(0)?))
+        } else {
+            None
+        };
+
+        let range = Expr::Range {
+            start: Box::new(expr),
+            end: Box::new(end),
+            step,
+            inclusive,
+        };
+
+        Ok(self.spanned(range, start))
     }
 
     fn parse_expression_with_precedence(
@@ -327,6 +995,7 @@ impl<'a> Parser<'a> {
         min_precedence: u8,
     ) -> Result<Expr, error::ParseError> {
         // Start by parsing the primary expression (the base)
+        let start = self.current_span;
         let mut left_expr = self.parse_primary()?;
 
         // While the current token is an operator and its precedence is higher than min_precedence
@@ -344,17 +1013,26 @@ impl<'a> Parser<'a> {
             let right_expr = self.parse_expression_with_precedence(op_precedence + 1)?;
 
             // Combine left and right expressions into a BinOp node
-            left_expr = Expr::BinOp {
+            let binop = Expr::BinOp {
                 left: Box::new(left_expr),
                 op: op_token,
                 right: Box::new(right_expr),
             };
+            left_expr = self.spanned(binop, start);
         }
 
         Ok(left_expr)
     }
 
+    fn spanned(&self, expr: Expr, start: Span) -> Expr {
+        Expr::Spanned {
+            expr: Box::new(expr),
+            span: self.span_from(start),
+        }
+    }
+
     fn parse_primary(&mut self) -> Result<Expr, error::ParseError> {
+        let start = self.current_span;
         let thing = match self.current_token.clone() {
             Token::Mew => {
                 self.next_token()?;
@@ -375,11 +1053,19 @@ impl<'a> Parser<'a> {
                     Ok(Expr::NewInstance { class_name, args })
                 } else {
                     Err(error::ParseError::GeneralError {
-                        line: self.lexer.line,
+                        span: self.current_span,
                         message: "Expected class name after 'new'".to_string(),
                     })
                 }
             }
+            Token::Not => {
+                self.next_token()?;
+
+                // Looser than comparisons, so not a rizz b is not (a rizz b)
+                let value = self.parse_expression_with_precedence(2)?;
+
+                Ok(Expr::Not(Box::new(value)))
+            }
             Token::Minus => {
                 // Move past the minus sign
                 self.next_token()?;
@@ -387,11 +1073,13 @@ impl<'a> Parser<'a> {
                 // Parse the next expression as the value of the negative number
                 let value = self.parse_primary()?;
 
-                match value {
+                match value.unspanned() {
                     Expr::Number(num) => Ok(Expr::Number(-num)),
+                    // -9223372036854775808 is only an i64 once it's negative
+                    Expr::BigNumber(num) => Ok(Expr::whole_number(-num)),
                     _ => Err(error::ParseError::GeneralError {
-                        line: self.lexer.line,
-                        message: format!("Expected a number after '-', found {:?}", value),
+                        span: self.current_span,
+                        message: format!("Expected a number after '-', found a {}", value.type_name()),
                     }),
                 }
             }
@@ -462,7 +1150,7 @@ impl<'a> Parser<'a> {
                         }
                     } else {
                         return Err(error::ParseError::UnexpectedToken {
-                            line: self.lexer.line,
+                            span: self.current_span,
                             expected: Token::Ident("variable name after .".into()),
                             found: self.current_token.clone(),
                         });
@@ -499,6 +1187,11 @@ impl<'a> Parser<'a> {
 
                 Ok(Expr::Number(value))
             }
+            Token::BigNumber(value) => {
+                self.next_token()?;
+
+                Ok(Expr::BigNumber(value))
+            }
             Token::StringLiteral(ref string) => {
                 let value = string.clone();
                 self.next_token()?;
@@ -517,24 +1210,28 @@ impl<'a> Parser<'a> {
             }
             _ => Err(error::ParseError::UnknownUnexpectedToken {
                 found: self.current_token.clone(),
-                line: self.lexer.line,
+                span: self.current_span,
             }),
         };
 
-        thing
+        Ok(self.spanned(thing?, start))
     }
 
     fn get_precedence(&self, token: &Token) -> Option<u8> {
         match token {
-            Token::Plus | Token::Minus => Some(1),
-            Token::Star | Token::Slash => Some(2),
-            Token::Rizz => Some(0), // Equality operator
-            Token::GreaterThan | Token::LessThan => Some(1), // Relational operators
+            Token::Or => Some(0),
+            Token::And => Some(1),
+            Token::Rizz => Some(2), // Equality operator
+            Token::GreaterThan | Token::LessThan => Some(3), // Relational operators
+            Token::Plus | Token::Minus => Some(4),
+            Token::Star | Token::Slash => Some(5),
             _ => None,
         }
     }
 
     fn parse_cook_statement(&mut self) -> Result<Stmt, error::ParseError> {
+        let start = self.current_span;
+
         // We've encountered 'cook', so advance the token.
         self.expect_token(Token::Cook)?;
 
@@ -543,9 +1240,9 @@ impl<'a> Parser<'a> {
             ident.clone()
         } else {
             return Err(error::ParseError::GeneralError {
-                line: self.lexer.line,
+                span: self.current_span,
                 message: format!(
-                    "Expected a function or object name after 'cook', but found: {:?}",
+                    "Expected a function or object name after 'cook', but found `{}`",
                     self.current_token
                 ),
             });
@@ -587,14 +1284,14 @@ impl<'a> Parser<'a> {
                         };
                     } else {
                         return Err(error::ParseError::UnexpectedToken {
-                            line: self.lexer.line,
+                            span: self.current_span,
                             expected: Token::LeftParen,
                             found: self.current_token.clone(),
                         });
                     }
                 } else {
                     return Err(error::ParseError::UnexpectedToken {
-                        line: self.lexer.line,
+                        span: self.current_span,
                         expected: Token::Ident("method name after .".into()),
                         found: self.current_token.clone(),
                     });
@@ -628,21 +1325,23 @@ impl<'a> Parser<'a> {
 
         // We assume that this function call is the entire statement.
         Ok(Stmt::Expression {
-            value: function_call,
-            line: self.lexer.line,
+            value: self.spanned(function_call, start),
+            span: self.span_from(start),
         })
     }
 
     fn parse_import_statement(&mut self) -> Result<Stmt, error::ParseError> {
+        let start = self.current_span;
+
         self.expect_token(Token::Gyatt)?;
 
         let lib_name = if let Token::Ident(ident) = &self.current_token {
             ident.clone()
         } else {
             return Err(error::ParseError::GeneralError {
-                line: self.lexer.line,
+                span: self.current_span,
                 message: format!(
-                    "Expected a library name after 'gyatt', found {:?}",
+                    "Expected a library name after 'gyatt', found `{}`",
                     self.current_token
                 ),
             });
@@ -652,11 +1351,13 @@ impl<'a> Parser<'a> {
 
         Ok(Stmt::Import {
             library: lib_name,
-            line: self.lexer.line,
+            span: self.span_from(start),
         })
     }
 
     fn parse_while(&mut self) -> Result<Stmt, error::ParseError> {
+        let start = self.current_span;
+
         self.expect_token(Token::Skibidi)?;
         self.expect_token(Token::LeftParen)?;
 
@@ -666,67 +1367,72 @@ impl<'a> Parser<'a> {
 
         self.expect_token(Token::Eat)?;
 
-        let mut body = Vec::new();
-
-        while self.current_token != Token::Slay && self.current_token != Token::EOF {
-            body.push(self.parse_statement()?);
-        }
+        let body = self.parse_block(&[Token::Slay]);
 
         self.expect_token(Token::Slay)?;
 
         Ok(Stmt::While {
             condition,
             body,
-            line: self.lexer.line,
+            span: self.span_from(start),
         })
     }
 
     fn parse_for_loop(&mut self) -> Result<Stmt, error::ParseError> {
+        let start = self.current_span;
+
         self.next_token()?; // Move past 'for'
         self.expect_token(Token::LeftParen)?;
 
-        let iterator = if let Token::Ident(name) = &self.current_token {
-            name.clone()
-        } else {
-            return Err(error::ParseError::GeneralError {
-                line: self.lexer.line,
-                message: "Expected iterator variable name".to_string(),
-            });
-        };
+        // goon (x, y in points) unpacks every element
+        let mut iterators = Vec::new();
+        loop {
+            let iterator = if let Token::Ident(name) = &self.current_token {
+                // Reported without stopping, the rest of the loop still parses fine
+                if let Err(e) = self.check_not_constant(name, self.current_span) {
+                    self.errors.push(e);
+                }
 
-        self.next_token()?;
-        self.expect_token(Token::In)?;
+                name.clone()
+            } else {
+                return Err(error::ParseError::GeneralError {
+                    span: self.current_span,
+                    message: "Expected iterator variable name".to_string(),
+                });
+            };
 
-        let collection = self.parse_expression()?;
+            iterators.push(iterator);
+            self.next_token()?;
 
-        // error if collection is not a list
-        if let Expr::List(_) = collection {
-        } else {
-            return Err(error::ParseError::GeneralError {
-                line: self.lexer.line,
-                message: "Expected list after 'in'".to_string(),
-            });
+            if self.current_token == Token::Comma {
+                self.next_token()?;
+            } else {
+                break;
+            }
         }
 
+        self.expect_token(Token::In)?;
+
+        let collection = self.parse_expression()?;
+
         self.expect_token(Token::RightParen)?;
         self.expect_token(Token::Eat)?;
 
-        let mut body = Vec::new();
-        while self.current_token != Token::Slay && self.current_token != Token::EOF {
-            body.push(self.parse_statement()?);
-        }
+        let body = self.parse_block(&[Token::Slay]);
 
         self.expect_token(Token::Slay)?;
 
         Ok(Stmt::ForLoop {
-            iterator,
+            iterators,
             collection,
             body,
-            line: self.lexer.line,
+            span: self.span_from(start),
         })
     }
 
     fn parse_if(&mut self) -> Result<Stmt, error::ParseError> {
+        let start = self.current_span;
+
         self.expect_token(Token::Suspect)?;
         self.expect_token(Token::LeftParen)?;
 
@@ -736,22 +1442,11 @@ impl<'a> Parser<'a> {
 
         self.expect_token(Token::Eat)?;
 
-        let mut then_branch = Vec::new();
-
-        while self.current_token != Token::Cap
-            && self.current_token != Token::Slay
-            && self.current_token != Token::EOF
-        {
-            then_branch.push(self.parse_statement()?);
-        }
+        let then_branch = self.parse_block(&[Token::Cap, Token::Slay]);
 
         let else_branch = if self.current_token == Token::Cap {
             self.next_token()?;
-            let mut else_stmts = Vec::new();
-            while self.current_token != Token::Slay && self.current_token != Token::EOF {
-                else_stmts.push(self.parse_statement()?);
-            }
-            Some(else_stmts)
+            Some(self.parse_block(&[Token::Slay]))
         } else {
             None
         };
@@ -762,26 +1457,246 @@ impl<'a> Parser<'a> {
             condition,
             then_branch,
             else_branch,
-            line: self.lexer.line,
+            span: self.span_from(start),
         })
     }
 
     fn parse_return(&mut self) -> Result<Stmt, error::ParseError> {
+        let start = self.current_span;
+
         self.expect_token(Token::Blud)?;
 
-        let expr = self.parse_expression()?;
+        let mut expr = self.parse_expression()?;
+
+        // blud x, y hands back both as a list, ready to be unpacked
+        if self.current_token == Token::Comma {
+            let mut values = vec![expr];
+
+            while self.current_token == Token::Comma {
+                self.next_token()?;
+                values.push(self.parse_expression()?);
+            }
+
+            expr = Expr::List(values);
+        }
 
         Ok(Stmt::Return {
             value: expr,
-            line: self.lexer.line,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_yield(&mut self) -> Result<Stmt, error::ParseError> {
+        let start = self.current_span;
+
+        self.expect_token(Token::Yeet)?;
+
+        let value = self.parse_expression()?;
+
+        Ok(Stmt::Yield {
+            value,
+            span: self.span_from(start),
         })
     }
 
     fn parse_continue(&mut self) -> Result<Stmt, error::ParseError> {
+        let start = self.current_span;
+
         self.expect_token(Token::Ghost)?;
 
         Ok(Stmt::Continue {
-            line: self.lexer.line,
+            span: self.span_from(start),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> (Vec<Stmt>, Vec<error::ParseError>) {
+        let mut lexer = Lexer::new(source);
+        Parser::new(&mut lexer).unwrap().parse()
+    }
+
+    #[test]
+    fn reassigning_a_constant_is_an_error() {
+        let (_, errors) = parse("based X is 1\nX is 2\n");
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code(), "E0007");
+    }
+
+    #[test]
+    fn goon_over_a_constant_is_an_error() {
+        let (statements, errors) = parse("\
+based X is 1
+goon (X in [5, 6]) eat
+    cook yap(X)
+slay
+");
+
+        // Just the one, the loop still parses
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code(), "E0007");
+        assert!(matches!(statements.last(), Some(Stmt::ForLoop { .. })));
+    }
+
+    #[test]
+    fn vibecheck_binding_a_constant_is_an_error() {
+        let source = "\
+based SCORE is 1
+vibecheck (state) eat
+    S.Over(SCORE) eat
+        cook yap(SCORE)
+    slay
+slay
+";
+        let (statements, errors) = parse(source);
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code(), "E0007");
+        assert!(matches!(statements.last(), Some(Stmt::Match { .. })));
+    }
+
+    #[test]
+    fn patterns() {
+        let source = "\
+vibecheck (state) eat
+    S.Over(score, _) eat
+    slay
+    S.Menu eat
+    slay
+    _ eat
+    slay
+slay
+";
+        let (statements, errors) = parse(source);
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        let Some(Stmt::Match { arms, .. }) = statements.first() else {
+            panic!("not a vibecheck: {:?}", statements);
+        };
+
+        assert!(matches!(
+            &arms[0].pattern,
+            Pattern::Variant { enum_name, variant, bindings }
+                if enum_name == "S" && variant == "Over" && bindings == &["score", "_"]
+        ));
+        assert!(matches!(arms[1].pattern, Pattern::Value(_)));
+        assert!(matches!(arms[2].pattern, Pattern::Wildcard));
+        assert_eq!((arms[0].span.line, arms[0].span.column), (2, 5));
+    }
+
+    #[test]
+    fn a_repeated_variant_points_at_itself() {
+        let (_, errors) = parse("vibe S\n    Over\n    Win(score)\n    Over\nslay\n");
+        let span = errors[0].span().unwrap();
+
+        assert_eq!(errors[0].message(), "Variant Over is already in vibe S");
+        assert_eq!((span.line, span.column, span.end_line, span.end_column), (4, 5, 4, 9));
+    }
+
+    #[test]
+    fn destructuring_a_constant_is_an_error() {
+        let (_, errors) = parse("based X is 1\nX, y is [1, 2]\n");
+
+        assert_eq!(errors[0].code(), "E0007");
+    }
+
+    #[test]
+    fn constants() {
+        let (statements, errors) = parse("based FPS: number is 60\n");
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        assert!(matches!(
+            &statements[0],
+            Stmt::Constant { name, type_annotation: Some(Type::Number), value, .. }
+                if name == "FPS" && value.unspanned() == &Expr::Number(60)
+        ));
+    }
+
+    #[test]
+    fn declaring_a_constant_twice_is_an_error() {
+        let (_, errors) = parse("based X is 1\nbased X is 2\n");
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code(), "E0007");
+        assert_eq!(errors[0].span().map(|span| span.line), Some(2));
+    }
+
+    #[test]
+    fn based_needs_a_name() {
+        let (_, errors) = parse("based 1 is 2\n");
+
+        assert_eq!(errors[0].code(), "E0005");
+    }
+
+    #[test]
+    fn statements_span_their_whole_text() {
+        let (statements, errors) = parse("cookable f(a)\n    blud a + 1\nslay\nx is cook f(1)\n");
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        let spans: Vec<(usize, usize, usize, usize)> = statements
+            .iter()
+            .map(|stmt| stmt.span())
+            .map(|span| (span.line, span.column, span.end_line, span.end_column))
+            .collect();
+        assert_eq!(spans, vec![(1, 1, 3, 5), (4, 1, 4, 15)]);
+
+        let Stmt::Function { body, .. } = &statements[0] else {
+            panic!("not a cookable: {:?}", statements[0]);
+        };
+        let Stmt::Return { value, .. } = &body[0] else {
+            panic!("not a blud: {:?}", body[0]);
+        };
+        let Expr::Spanned { span, .. } = value else {
+            panic!("no span: {:?}", value);
+        };
+        assert_eq!((span.line, span.column, span.end_column), (2, 10, 15));
+    }
+
+    #[test]
+    fn every_error_is_reported() {
+        let (statements, errors) = parse("\
+x is is 1
+y is 2
+z is )
+w is 3
+");
+
+        let lines: Vec<usize> = errors.iter().filter_map(|e| e.span()).map(|span| span.line).collect();
+        assert_eq!(lines, vec![1, 3]);
+        // The good statements around them still parse
+        let names: Vec<&str> = statements
+            .iter()
+            .filter_map(|stmt| match stmt {
+                Stmt::VariableAssign { name, .. } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(names, vec!["y", "w"]);
+    }
+
+    #[test]
+    fn an_error_in_a_block_keeps_the_block() {
+        let (statements, errors) = parse("\
+cookable f()
+    x is is 1
+    blud 2
+slay
+
+cookable g()
+slay
+");
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].span().map(|span| span.line), Some(2));
+        assert_eq!(statements.len(), 2);
+
+        let Stmt::Function { body, .. } = &statements[0] else {
+            panic!("not a cookable: {:?}", statements[0]);
+        };
+        assert!(matches!(body.last(), Some(Stmt::Return { .. })));
+    }
+}
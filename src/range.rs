@@ -0,0 +1,134 @@
+// range.rs
+
+// Walks a range value one number at a time, so goon loops never build a list for it
+
+pub struct RangeIter {
+    current: i64,
+    end: i64,
+    step: i64,
+    inclusive: bool,
+    done: bool,
+}
+
+impl RangeIter {
+    pub fn new(start: i64, end: i64, step: i64, inclusive: bool) -> Self {
+        RangeIter {
+            current: start,
+            end,
+            step,
+            inclusive,
+            done: step == 0,
+        }
+    }
+
+    // How many numbers are left, worked out from the ends so a huge range isn't walked.
+    // i128 because a range over every number has more of them than an i64 can hold
+    pub fn count_left(&self) -> i128 {
+        if self.done {
+            return 0;
+        }
+
+        let step = i128::from(self.step);
+        let distance = (i128::from(self.end) - i128::from(self.current)) * step.signum();
+        let step = step.abs();
+
+        match self.inclusive {
+            true if distance >= 0 => distance / step + 1,
+            false if distance > 0 => (distance - 1) / step + 1,
+            _ => 0,
+        }
+    }
+}
+
+impl Iterator for RangeIter {
+    type Item = i64;
+
+    fn next(&mut self) -> Option<i64> {
+        if self.done {
+            return None;
+        }
+
+        // Counting down works the same, just the other way around
+        let in_range = match (self.step > 0, self.inclusive) {
+            (true, true) => self.current <= self.end,
+            (true, false) => self.current < self.end,
+            (false, true) => self.current >= self.end,
+            (false, false) => self.current > self.end,
+        };
+
+        if !in_range {
+            self.done = true;
+            return None;
+        }
+
+        let value = self.current;
+
+        match self.current.checked_add(self.step) {
+            Some(next) => self.current = next,
+            None => self.done = true,
+        }
+
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(start: i64, end: i64, step: i64, inclusive: bool) -> Vec<i64> {
+        RangeIter::new(start, end, step, inclusive).collect()
+    }
+
+    #[test]
+    fn counting_up() {
+        assert_eq!(collect(0, 4, 1, false), vec![0, 1, 2, 3]);
+        assert_eq!(collect(0, 4, 1, true), vec![0, 1, 2, 3, 4]);
+        assert_eq!(collect(0, 10, 3, false), vec![0, 3, 6, 9]);
+    }
+
+    #[test]
+    fn counting_down() {
+        assert_eq!(collect(3, 0, -1, false), vec![3, 2, 1]);
+        assert_eq!(collect(3, 0, -2, true), vec![3, 1]);
+    }
+
+    #[test]
+    fn empty_ranges() {
+        assert!(collect(0, 0, 1, false).is_empty());
+        assert!(collect(5, 0, 1, true).is_empty());
+        // A step of 0 would never get anywhere
+        assert!(collect(0, 10, 0, false).is_empty());
+    }
+
+    #[test]
+    fn counting_without_walking() {
+        for (start, end, step, inclusive) in [
+            (0, 4, 1, false),
+            (0, 4, 1, true),
+            (0, 10, 3, false),
+            (0, 9, 3, true),
+            (3, 0, -1, false),
+            (3, 0, -2, true),
+            (0, 0, 1, false),
+            (5, 0, 1, true),
+            (0, 10, 0, false),
+        ] {
+            let expected = collect(start, end, step, inclusive).len() as i128;
+            assert_eq!(RangeIter::new(start, end, step, inclusive).count_left(), expected);
+        }
+
+        let mut range = RangeIter::new(0, 10, 1, false);
+        range.next();
+        assert_eq!(range.count_left(), 9);
+
+        assert_eq!(RangeIter::new(0, i64::MAX, 1, false).count_left(), i64::MAX as i128);
+        assert_eq!(RangeIter::new(i64::MIN, i64::MAX, 1, true).count_left(), 1 << 64);
+    }
+
+    #[test]
+    fn stops_at_the_edge_instead_of_overflowing() {
+        assert_eq!(collect(i64::MAX - 1, i64::MAX, 1, true), vec![i64::MAX - 1, i64::MAX]);
+        assert_eq!(collect(i64::MIN + 1, i64::MIN, -1, true), vec![i64::MIN + 1, i64::MIN]);
+    }
+}
@@ -0,0 +1,387 @@
+// repl.rs
+
+// `skibidyscript repl`, one interpreter that stays alive between inputs so variables, cookables
+// and imports carry over. An input with an `eat` or `cookable` that hasn't been closed with
+// `slay` yet keeps reading lines until it is. An input that's just an expression gets its value
+// yapped back.
+
+use rustyline::{error::ReadlineError, DefaultEditor};
+
+use crate::{
+    cli::Options,
+    diagnostic::{self, Diagnostic},
+    interpreter::Interpreter,
+    lexer::{Lexer, Token},
+    parser::{Expr, Parser},
+    typecheck,
+};
+
+const PROMPT: &str = "skibidi> ";
+const CONTINUE_PROMPT: &str = "     ... ";
+const HISTORY_FILE: &str = ".skibidi_history";
+
+const HELP: &str = "\
+Type statements or expressions, blocks keep going until their slay.
+    :vars        variables and their values
+    :functions   cookables and imported libraries
+    :classes     pookies and vibes
+    :help        show this
+    :quit        leave, ctrl-d works too";
+
+pub struct Repl {
+    interpreter: Interpreter,
+    // Everything typed so far. Each input is lexed starting at the line after the last one,
+    // so errors in a cookable from an earlier input still point at the right line
+    session: String,
+    filename: String,
+}
+
+impl Repl {
+    pub fn new(options: &Options) -> Self {
+        let mut interpreter = Interpreter::new();
+        interpreter.strict = options.strict;
+
+        Repl {
+            interpreter,
+            session: String::new(),
+            filename: "<repl>".to_string(),
+        }
+    }
+
+    // A script given on the command line runs its top level first, its cookables are there to play with
+    pub fn load(&mut self, filename: &str, source: &str) -> bool {
+        let previous = std::mem::replace(&mut self.filename, filename.to_string());
+        let loaded = self.eval(source, false);
+        self.filename = previous;
+
+        loaded
+    }
+
+    fn report(&self, diagnostic: Diagnostic) {
+        eprint!(
+            "{}",
+            diagnostic.render(&self.session, &self.filename, diagnostic::use_color(&std::io::stderr()))
+        );
+    }
+
+    // Runs one complete input, false if it went wrong
+    fn eval(&mut self, input: &str, echo: bool) -> bool {
+        let first_line = self.session.lines().count() + 1;
+        self.session += input;
+        self.session += "\n";
+
+        // Just an expression, which gets yapped back
+        if echo {
+            if let Some(expr) = parse_lone_expression(input, first_line) {
+                return self.echo(expr);
+            }
+        }
+
+        let mut lexer = Lexer::new(input);
+        lexer.line = first_line;
+
+        let statements = match Parser::new(&mut lexer).map(|mut parser| parser.parse()) {
+            Ok((statements, errors)) if errors.is_empty() => statements,
+            Ok((_, errors)) => {
+                for e in &errors {
+                    self.report(Diagnostic::from(e));
+                }
+
+                return false;
+            }
+            Err(e) => {
+                self.report(Diagnostic::from(&e));
+
+                return false;
+            }
+        };
+
+        let type_errors = typecheck::check(&statements);
+        if !type_errors.is_empty() {
+            for e in &type_errors {
+                self.report(Diagnostic::from(e));
+            }
+
+            return false;
+        }
+
+        match self.interpreter.interpret(statements) {
+            Ok(()) => true,
+            Err(e) => {
+                self.report(Diagnostic::from(&e));
+
+                false
+            }
+        }
+    }
+
+    fn echo(&mut self, expr: Expr) -> bool {
+        // yap already said everything, what it gives back isn't interesting
+        let is_yap = matches!(expr.unspanned(), Expr::FunctionCall { name, object: None, .. } if name == "yap");
+
+        let result = self
+            .interpreter
+            .evaluate_expression(expr)
+            .and_then(|value| self.interpreter.format_value(value, true));
+
+        match result {
+            Ok(_) if is_yap => true,
+            Ok(text) => {
+                println!("{}", text);
+
+                true
+            }
+            Err(e) => {
+                self.report(Diagnostic::from(&e));
+
+                false
+            }
+        }
+    }
+
+    // :vars and friends, anything after the name is ignored
+    fn meta_command(&mut self, command: &str) -> bool {
+        match command.split_whitespace().next().unwrap_or_default() {
+            ":quit" | ":q" => return false,
+            ":help" | ":h" => println!("{}", HELP),
+            ":vars" => {
+                let mut names: Vec<String> = self.interpreter.variables.keys().cloned().collect();
+                names.sort();
+
+                for name in names {
+                    let value = self.interpreter.variables[&name].clone();
+                    let based = if self.interpreter.constants.contains_key(&name) {
+                        "based "
+                    } else {
+                        ""
+                    };
+
+                    match self.interpreter.format_value(value, true) {
+                        Ok(text) => println!("{}{} is {}", based, name, text),
+                        Err(e) => self.report(Diagnostic::from(&e)),
+                    }
+                }
+            }
+            ":functions" => {
+                let mut functions: Vec<String> = self
+                    .interpreter
+                    .functions
+                    .values()
+                    .map(|function| {
+                        let params: Vec<&str> = function.params.iter().map(|param| param.name.as_str()).collect();
+
+                        format!("cookable {}({})", function.name, params.join(", "))
+                    })
+                    .collect();
+                functions.sort();
+
+                for function in functions {
+                    println!("{}", function);
+                }
+
+                let mut libs: Vec<(&String, usize)> = self
+                    .interpreter
+                    .libs
+                    .iter()
+                    .map(|(name, lib)| (name, lib.functions.len()))
+                    .collect();
+                libs.sort();
+
+                for (name, count) in libs {
+                    println!("gyatt {} ({} cookables)", name, count);
+                }
+            }
+            ":classes" => {
+                let mut classes: Vec<(&String, Vec<&String>)> = self
+                    .interpreter
+                    .classes
+                    .iter()
+                    .map(|(name, class)| {
+                        let mut methods: Vec<&String> = class.functions.keys().collect();
+                        methods.sort();
+
+                        (name, methods)
+                    })
+                    .collect();
+                classes.sort();
+
+                for (name, methods) in classes {
+                    let methods: Vec<&str> = methods.iter().map(|method| method.as_str()).collect();
+                    println!("pookie {} with {}", name, methods.join(", "));
+                }
+
+                let mut enums: Vec<(&String, Vec<&str>)> = self
+                    .interpreter
+                    .enums
+                    .iter()
+                    .map(|(name, variants)| (name, variants.iter().map(|variant| variant.name.as_str()).collect()))
+                    .collect();
+                enums.sort();
+
+                for (name, variants) in enums {
+                    println!("vibe {} with {}", name, variants.join(", "));
+                }
+            }
+            other => eprintln!("unknown command {}, :help lists them", other),
+        }
+
+        true
+    }
+}
+
+fn parse_lone_expression(input: &str, first_line: usize) -> Option<Expr> {
+    let mut lexer = Lexer::new(input);
+    lexer.line = first_line;
+
+    let mut parser = Parser::new(&mut lexer).ok()?;
+    parser.parse_lone_expression().ok()
+}
+
+// Blocks still open at the end of the input, so the repl knows to keep reading.
+// A lexer error counts as done, so it gets reported instead of waiting forever
+fn open_blocks(input: &str) -> i64 {
+    let mut lexer = Lexer::new(input);
+    let mut depth = 0;
+
+    loop {
+        match lexer.next_token() {
+            Ok((Token::Cookable | Token::Pookie | Token::Vibe | Token::Eat, _)) => depth += 1,
+            Ok((Token::Slay, _)) => depth -= 1,
+            Ok((Token::EOF, _)) => return depth,
+            Ok(_) => {}
+            Err(_) => return 0,
+        }
+    }
+}
+
+fn history_path() -> Option<std::path::PathBuf> {
+    std::env::var_os("HOME").map(|home| std::path::Path::new(&home).join(HISTORY_FILE))
+}
+
+pub fn run(mut repl: Repl) -> i32 {
+    let mut editor = match DefaultEditor::new() {
+        Ok(editor) => editor,
+        Err(e) => {
+            eprintln!("error: couldn't start the repl: {}", e);
+
+            return 1;
+        }
+    };
+
+    let history = history_path();
+    if let Some(history) = &history {
+        let _ = editor.load_history(history);
+    }
+
+    println!("skibidiscript repl, :help for help");
+
+    let mut input = String::new();
+
+    loop {
+        let prompt = if input.is_empty() { PROMPT } else { CONTINUE_PROMPT };
+
+        let line = match editor.readline(prompt) {
+            Ok(line) => line,
+            // ctrl-c throws away what's been typed, ctrl-d leaves
+            Err(ReadlineError::Interrupted) => {
+                input.clear();
+                continue;
+            }
+            Err(ReadlineError::Eof) => break,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                break;
+            }
+        };
+
+        if input.is_empty() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if line.trim_start().starts_with(':') {
+                let _ = editor.add_history_entry(line.as_str());
+
+                if !repl.meta_command(line.trim()) {
+                    break;
+                }
+
+                continue;
+            }
+        }
+
+        input += &line;
+        input += "\n";
+
+        if open_blocks(&input) > 0 {
+            continue;
+        }
+
+        let _ = editor.add_history_entry(input.trim_end());
+        repl.eval(input.trim_end(), true);
+        input.clear();
+    }
+
+    if let Some(history) = &history {
+        let _ = editor.save_history(history);
+    }
+
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli;
+
+    fn repl() -> Repl {
+        let mut repl = Repl::new(&cli::parse_args(&["skibidyscript".to_string()]).unwrap());
+        repl.interpreter.output = Box::new(std::io::sink());
+
+        repl
+    }
+
+    #[test]
+    fn blocks_keep_reading_until_their_slay() {
+        assert_eq!(open_blocks("x is 1"), 0);
+        assert_eq!(open_blocks("cookable f()"), 1);
+        assert_eq!(open_blocks("cookable f()\n    sus (sigma) eat"), 2);
+        assert_eq!(open_blocks("cookable f()\n    sus (sigma) eat\n    slay\nslay"), 0);
+        // -- slay is a comment, and "slay" is a string, neither closes anything
+        assert_eq!(open_blocks("vibe S -- slay\n    \"slay\""), 1);
+    }
+
+    #[test]
+    fn inputs_share_one_interpreter() {
+        let mut repl = repl();
+
+        assert!(repl.eval("cookable double(x)\n    blud x * 2\nslay", true));
+        assert!(repl.eval("y is cook double(21)", true));
+        assert!(repl.eval("y", true));
+        assert_eq!(repl.interpreter.variables.get("y"), Some(&Expr::Number(42)));
+    }
+
+    #[test]
+    fn a_bad_input_doesnt_lose_anything() {
+        let mut repl = repl();
+
+        assert!(repl.eval("based X is 1", true));
+        assert!(!repl.eval("X is 2", true));
+        assert!(!repl.eval("z is cook nope()", true));
+        assert_eq!(repl.interpreter.variables.get("X"), Some(&Expr::Number(1)));
+
+        // Kept, bad inputs too, so later errors still point at the right line
+        assert_eq!(repl.session.lines().count(), 3);
+    }
+
+    #[test]
+    fn loading_a_script_keeps_its_cookables() {
+        let mut repl = repl();
+
+        assert!(repl.load("game.skibidi", "cookable f()\n    blud 1\nslay\n"));
+        assert!(repl.interpreter.functions.contains_key("f"));
+        assert_eq!(repl.filename, "<repl>");
+        assert!(!repl.meta_command(":quit"));
+        assert!(repl.meta_command(":vars"));
+    }
+}
@@ -0,0 +1,94 @@
+// suggest.rs
+
+// "did you mean" for names that don't exist. Library functions are camelCase and long
+// (setFramesPerSkibidi), so a typo somewhere in the middle is easy to make and hard to see
+
+use crate::lexer::Lexer;
+
+// The closest candidate that's near enough to be what was meant
+pub fn did_you_mean<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    // Longer names get to have more typos in them. Some of the name has to stay the same, or
+    // every one letter name would be a typo of every other one
+    let length = name.chars().count();
+    let max_distance = (length / 3).max(1).min(length.saturating_sub(1));
+
+    candidates
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        // Case is the most common mistake, so it doesn't count
+        .map(|candidate| (distance(&name.to_lowercase(), &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.to_string())
+}
+
+// Edit distance, how many characters have to be added, removed or changed. Two letters the
+// wrong way round count as one mistake, not two
+pub fn distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // table[i][j] is the distance between the first i chars of a and the first j of b
+    let mut table = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in table.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in table[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+
+            let mut best = (table[i - 1][j] + 1)
+                .min(table[i][j - 1] + 1)
+                .min(table[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(table[i - 2][j - 2] + 1);
+            }
+
+            table[i][j] = best;
+        }
+    }
+
+    table[a.len()][b.len()]
+}
+
+pub fn keywords() -> Vec<String> {
+    Lexer::new("").keyword_names()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typos_get_a_suggestion() {
+        let names = ["setFramesPerSkibidi", "clockEdge", "score"];
+
+        assert_eq!(
+            did_you_mean("setFramesPerSkbidi", names),
+            Some("setFramesPerSkibidi".to_string())
+        );
+        assert_eq!(did_you_mean("scroe", names), Some("score".to_string()));
+        assert_eq!(did_you_mean("clockedge", names), Some("clockEdge".to_string()));
+        assert_eq!(did_you_mean("banana", names), None);
+    }
+
+    #[test]
+    fn short_names_dont_match_each_other() {
+        assert_eq!(did_you_mean("y", ["x"]), None);
+        assert_eq!(did_you_mean("X", ["x"]), Some("x".to_string()));
+        assert_eq!(did_you_mean("ab", ["ba", "xy"]), Some("ba".to_string()));
+    }
+
+    #[test]
+    fn swapped_letters_are_one_mistake() {
+        assert_eq!(distance("yap", "yap"), 0);
+        assert_eq!(distance("yap", "ypa"), 1);
+        assert_eq!(distance("goon", "gon"), 1);
+        assert_eq!(distance("", "slay"), 4);
+    }
+}
@@ -0,0 +1,889 @@
+// typecheck.rs
+
+// Static pass over the parsed statements. It runs before the interpreter so type mistakes
+// are reported up front instead of deep inside a game loop. Annotations are optional,
+// anything the checker can't figure out is `any` and always passes.
+
+use std::collections::HashMap;
+
+use crate::{
+    error,
+    generator,
+    lexer::{Span, Token},
+    libs::{self, LibSignatures, Signature},
+    parser::{EnumVariant, Expr, Pattern, Stmt, Type, BUILTIN_TYPES},
+    suggest,
+};
+
+pub struct TypeChecker {
+    globals: HashMap<String, Type>,
+    annotated: HashMap<String, Type>, // Globals with an explicit annotation
+    locals: Vec<HashMap<String, Type>>,
+    functions: HashMap<String, Signature>,
+    classes: HashMap<String, HashMap<String, Signature>>,
+    enums: HashMap<String, Vec<EnumVariant>>,
+    libs: HashMap<String, LibSignatures>,
+
+    // State of the function being checked
+    return_type: Option<Type>,
+    current_class: Option<String>,
+
+    errors: Vec<error::ParseError>,
+}
+
+pub fn check(statements: &[Stmt]) -> Vec<error::ParseError> {
+    let mut checker = TypeChecker::new();
+
+    checker.collect_declarations(statements);
+    checker.collect_assignments(statements);
+    checker.check_statements(statements);
+
+    checker.errors
+}
+
+fn accepts(expected: &Type, found: &Type) -> bool {
+    *expected == Type::Any || *found == Type::Any || expected == found
+}
+
+fn function_signature(
+    params: &[crate::parser::Param],
+    return_type: &Option<Type>,
+    body: &[Stmt],
+) -> Signature {
+    // Calling a generator always gives back the generator itself
+    let returns = if generator::body_yields(body) {
+        Type::Generator
+    } else {
+        return_type.clone().unwrap_or(Type::Any)
+    };
+
+    Signature::new(
+        params
+            .iter()
+            .map(|param| param.type_annotation.clone().unwrap_or(Type::Any))
+            .collect(),
+        returns,
+    )
+}
+
+impl TypeChecker {
+    pub fn new() -> Self {
+        TypeChecker {
+            globals: HashMap::new(),
+            annotated: HashMap::new(),
+            locals: Vec::new(),
+            functions: HashMap::new(),
+            classes: HashMap::new(),
+            enums: HashMap::new(),
+            libs: HashMap::new(),
+            return_type: None,
+            current_class: None,
+            errors: Vec::new(),
+        }
+    }
+
+    // First pass, functions, classes, imports and annotated globals
+    fn collect_declarations(&mut self, statements: &[Stmt]) {
+        for stmt in statements {
+            match stmt {
+                Stmt::Function {
+                    name,
+                    params,
+                    return_type,
+                    body,
+                    ..
+                } => {
+                    self.functions
+                        .insert(name.clone(), function_signature(params, return_type, body));
+                }
+                Stmt::Class {
+                    name, functions, ..
+                } => {
+                    let mut methods = HashMap::new();
+
+                    for method in functions {
+                        if let Stmt::Function {
+                            name: method_name,
+                            params,
+                            return_type,
+                            body,
+                            ..
+                        } = method
+                        {
+                            methods.insert(
+                                method_name.clone(),
+                                function_signature(params, return_type, body),
+                            );
+                        }
+                    }
+
+                    self.classes.insert(name.clone(), methods);
+                }
+                Stmt::Enum { name, variants, .. } => {
+                    self.enums.insert(name.clone(), variants.clone());
+                }
+                Stmt::Import { library, .. } => {
+                    if let Some(lib) = libs::load_library(library) {
+                        self.libs.insert(library.clone(), lib.signatures);
+                    }
+                }
+                Stmt::VariableAssign {
+                    name,
+                    object: None,
+                    type_annotation: Some(annotation),
+                    ..
+                }
+                | Stmt::Constant {
+                    name,
+                    type_annotation: Some(annotation),
+                    ..
+                } => {
+                    self.annotated.insert(name.clone(), annotation.clone());
+                    self.globals.insert(name.clone(), annotation.clone());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Second pass, unannotated globals get the type of their assignments if they all agree
+    fn collect_assignments(&mut self, statements: &[Stmt]) {
+        for stmt in statements {
+            match stmt {
+                Stmt::VariableAssign {
+                    name,
+                    object: None,
+                    type_annotation: None,
+                    value,
+                    span,
+                }
+                | Stmt::Constant {
+                    name,
+                    type_annotation: None,
+                    value,
+                    span,
+                } => {
+                    if self.annotated.contains_key(name) || self.is_local(name) {
+                        continue;
+                    }
+
+                    let found = self.infer_quietly(value, *span);
+
+                    let merged = match self.globals.get(name) {
+                        Some(existing) if *existing != found => Type::Any,
+                        _ => found,
+                    };
+
+                    self.globals.insert(name.clone(), merged);
+                }
+                Stmt::Function { params, body, .. } => {
+                    self.locals
+                        .push(params.iter().map(|param| (param.name.clone(), Type::Any)).collect());
+                    self.collect_assignments(body);
+                    self.locals.pop();
+                }
+                Stmt::Class { functions, .. } => self.collect_assignments(functions),
+                Stmt::While { body, .. } | Stmt::ForLoop { body, .. } => {
+                    self.collect_assignments(body)
+                }
+                Stmt::If {
+                    then_branch,
+                    else_branch,
+                    ..
+                } => {
+                    self.collect_assignments(then_branch);
+
+                    if let Some(else_branch) = else_branch {
+                        self.collect_assignments(else_branch);
+                    }
+                }
+                Stmt::Match { arms, .. } => {
+                    for arm in arms {
+                        self.collect_assignments(&arm.body);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn check_statements(&mut self, statements: &[Stmt]) {
+        for stmt in statements {
+            self.check_statement(stmt);
+        }
+    }
+
+    fn check_statement(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Class {
+                name, functions, ..
+            } => {
+                let previous_class = self.current_class.replace(name.clone());
+                self.check_statements(functions);
+                self.current_class = previous_class;
+            }
+            Stmt::Function {
+                params,
+                return_type,
+                body,
+                span,
+                ..
+            } => {
+                for annotation in params.iter().filter_map(|param| param.type_annotation.as_ref()) {
+                    self.check_annotation(annotation, *span);
+                }
+                if let Some(return_type) = return_type {
+                    self.check_annotation(return_type, *span);
+                }
+
+                self.locals.push(
+                    params
+                        .iter()
+                        .map(|param| {
+                            (
+                                param.name.clone(),
+                                param.type_annotation.clone().unwrap_or(Type::Any),
+                            )
+                        })
+                        .collect(),
+                );
+                // A generator's blud only ends it, the value goes nowhere
+                let return_type = if generator::body_yields(body) {
+                    None
+                } else {
+                    return_type.clone()
+                };
+                let previous_return = std::mem::replace(&mut self.return_type, return_type);
+
+                self.check_statements(body);
+
+                self.return_type = previous_return;
+                self.locals.pop();
+            }
+            Stmt::VariableAssign {
+                name,
+                object,
+                type_annotation,
+                value,
+                span,
+            } => {
+                let found = self.infer(value, *span);
+
+                if object.is_some() {
+                    return;
+                }
+
+                if let Some(annotation) = type_annotation {
+                    self.check_annotation(annotation, *span);
+                }
+
+                let expected = match type_annotation {
+                    Some(annotation) => Some(annotation.clone()),
+                    None => self
+                        .locals
+                        .last()
+                        .and_then(|frame| frame.get(name))
+                        .or_else(|| self.annotated.get(name))
+                        .cloned(),
+                };
+
+                if let Some(expected) = expected {
+                    self.expect_type(&expected, &found, format!("variable {}", name), *span);
+                }
+            }
+            Stmt::Constant {
+                name,
+                type_annotation,
+                value,
+                span,
+            } => {
+                let found = self.infer(value, *span);
+
+                if let Some(expected) = type_annotation {
+                    self.check_annotation(expected, *span);
+                    self.expect_type(expected, &found, format!("constant {}", name), *span);
+                }
+            }
+            Stmt::While {
+                condition,
+                body,
+                span,
+            } => {
+                self.infer(condition, *span);
+                self.check_statements(body);
+            }
+            Stmt::If {
+                condition,
+                then_branch,
+                else_branch,
+                span,
+            } => {
+                self.infer(condition, *span);
+                self.check_statements(then_branch);
+
+                if let Some(else_branch) = else_branch {
+                    self.check_statements(else_branch);
+                }
+            }
+            Stmt::ForLoop {
+                iterators,
+                collection,
+                body,
+                span,
+            } => {
+                let found = self.infer(collection, *span);
+
+                if found != Type::Range && found != Type::Generator {
+                    self.expect_type(&Type::List, &found, "goon collection".to_string(), *span);
+                }
+
+                // Ranges only ever give numbers, anything in a list or from a generator could be anything
+                let item = if found == Type::Range && iterators.len() == 1 {
+                    Type::Number
+                } else {
+                    Type::Any
+                };
+                let bindings = iterators.iter().map(|name| (name.clone(), item.clone())).collect();
+
+                self.check_scoped(bindings, body);
+            }
+            Stmt::DestructureAssign { value, span, .. } => {
+                let found = self.infer(value, *span);
+                self.expect_type(&Type::List, &found, "unpacked value".to_string(), *span);
+            }
+            Stmt::Expression { value, span } => {
+                self.infer(value, *span);
+            }
+            Stmt::Return { value, span } => {
+                let found = self.infer(value, *span);
+
+                if let Some(expected) = self.return_type.clone() {
+                    self.expect_type(&expected, &found, "return value".to_string(), *span);
+                }
+            }
+            Stmt::Match {
+                subject,
+                arms,
+                span,
+            } => {
+                self.infer(subject, *span);
+
+                for arm in arms {
+                    match &arm.pattern {
+                        // An arm for a variant that doesn't exist would never match
+                        Pattern::Variant {
+                            enum_name,
+                            variant,
+                            bindings,
+                        } => {
+                            let fields = self
+                                .enum_name_of(&Expr::Ident(enum_name.clone()))
+                                .and_then(|enum_name| self.variant_fields(&enum_name, variant, arm.span));
+
+                            if let Some(fields) = fields {
+                                if fields != bindings.len() {
+                                    self.errors.push(error::ParseError::ArgumentMismatch {
+                                        expected: fields,
+                                        found: bindings.len(),
+                                        span: arm.span,
+                                    });
+                                }
+                            }
+                        }
+                        Pattern::Value(value) => {
+                            self.infer(value, arm.span);
+                        }
+                        Pattern::Wildcard => {}
+                    }
+
+                    let bindings = match &arm.pattern {
+                        Pattern::Variant { bindings, .. } => {
+                            bindings.iter().map(|name| (name.clone(), Type::Any)).collect()
+                        }
+                        _ => Vec::new(),
+                    };
+
+                    self.check_scoped(bindings, &arm.body);
+                }
+            }
+            Stmt::Yield { value, span } => {
+                self.infer(value, *span);
+            }
+            Stmt::Import { .. } | Stmt::Continue { .. } | Stmt::Enum { .. } => {}
+        }
+    }
+
+    // A goon body or vibecheck arm, with its own names on top of whatever the cookable already has
+    fn check_scoped(&mut self, bindings: Vec<(String, Type)>, body: &[Stmt]) {
+        let mut frame = self.locals.last().cloned().unwrap_or_default();
+        frame.extend(bindings);
+
+        self.locals.push(frame);
+        self.check_statements(body);
+        self.locals.pop();
+    }
+
+    fn expect_type(&mut self, expected: &Type, found: &Type, context: String, span: Span) {
+        // An unknown type has had its own error already
+        if !accepts(expected, found) && self.is_known(expected) && self.is_known(found) {
+            self.errors.push(error::ParseError::TypeMismatch {
+                expected: expected.clone(),
+                found: found.clone(),
+                context,
+                span,
+            });
+        }
+    }
+
+    // Annotations that aren't built in have to name a pookie or a vibe, a typo would
+    // otherwise be taken for a class nobody declared and never match anything
+    fn check_annotation(&mut self, annotation: &Type, span: Span) {
+        let name = match annotation {
+            Type::Class(name) if !self.is_known(annotation) => name,
+            _ => return,
+        };
+
+        let candidates: Vec<&str> = BUILTIN_TYPES
+            .iter()
+            .copied()
+            .chain(self.classes.keys().map(|name| name.as_str()))
+            .chain(self.enums.keys().map(|name| name.as_str()))
+            .collect();
+
+        self.errors.push(error::ParseError::UnknownType {
+            name: name.clone(),
+            suggestion: suggest::did_you_mean(name, candidates),
+            span,
+        });
+    }
+
+    // How many fields the variant has, an error if the vibe doesn't have it
+    fn variant_fields(&mut self, enum_name: &str, variant: &str, span: Span) -> Option<usize> {
+        let variants = self.enums.get(enum_name)?;
+
+        if let Some(found) = variants.iter().find(|found| found.name == variant) {
+            return Some(found.fields.len());
+        }
+
+        let suggestion = suggest::did_you_mean(variant, variants.iter().map(|found| found.name.as_str()));
+        self.errors.push(error::ParseError::UnknownVariant {
+            enum_name: enum_name.to_string(),
+            variant: variant.to_string(),
+            suggestion,
+            span,
+        });
+
+        None
+    }
+
+    fn is_known(&self, found: &Type) -> bool {
+        match found {
+            Type::Class(name) => self.classes.contains_key(name) || self.enums.contains_key(name),
+            _ => true,
+        }
+    }
+
+    fn enum_name_of(&self, object: &Expr) -> Option<String> {
+        match object {
+            Expr::Ident(name)
+                if self.enums.contains_key(name)
+                    && !self.globals.contains_key(name)
+                    && !self.is_local(name) =>
+            {
+                Some(name.clone())
+            }
+            _ => None,
+        }
+    }
+
+    fn is_local(&self, name: &str) -> bool {
+        self.locals
+            .last()
+            .is_some_and(|frame| frame.contains_key(name))
+    }
+
+    // Inference without reporting anything, used while collecting
+    fn infer_quietly(&mut self, expr: &Expr, span: Span) -> Type {
+        let errors = self.errors.len();
+        let found = self.infer(expr, span);
+        self.errors.truncate(errors);
+
+        found
+    }
+
+    fn infer(&mut self, expr: &Expr, span: Span) -> Type {
+        match expr {
+            Expr::Spanned { expr, span } => self.infer(expr, *span),
+            Expr::Number(_) | Expr::BigNumber(_) => Type::Number,
+            Expr::StringLiteral(_) => Type::String,
+            Expr::Boolean(_) => Type::Boolean,
+            Expr::List(elements) => {
+                for element in elements {
+                    self.infer(element, span);
+                }
+
+                Type::List
+            }
+            Expr::Ident(name) => self
+                .locals
+                .last()
+                .and_then(|frame| frame.get(name))
+                .or_else(|| self.globals.get(name))
+                .cloned()
+                .unwrap_or(Type::Any),
+            Expr::BinOp { left, op, right } => {
+                let left_type = self.infer(left, span);
+                let right_type = self.infer(right, span);
+
+                self.infer_binop(op, left_type, right_type, span)
+            }
+            Expr::FunctionCall { name, object, args } => {
+                self.infer_call(name, object.as_deref(), args, span)
+            }
+            Expr::NewInstance { class_name, args } => {
+                let init = self
+                    .classes
+                    .get(class_name)
+                    .and_then(|methods| methods.get("__edge__"))
+                    .cloned();
+
+                if let Some(init) = init {
+                    self.check_call(&format!("{}.__edge__", class_name), &init, args, span);
+                }
+
+                Type::Class(class_name.clone())
+            }
+            Expr::Instance { class_name, .. } => Type::Class(class_name.clone()),
+            Expr::EnumVariant { enum_name, .. } => Type::Class(enum_name.clone()),
+            Expr::Generator { .. } => Type::Generator,
+            Expr::Wait(_) => Type::Any,
+            Expr::Not(value) => {
+                self.infer(value, span);
+
+                Type::Boolean
+            }
+            Expr::Range {
+                start, end, step, ..
+            } => {
+                for bound in [Some(start), Some(end), step.as_ref()].into_iter().flatten() {
+                    let found = self.infer(bound, span);
+                    self.expect_type(&Type::Number, &found, "range bound".to_string(), span);
+                }
+
+                Type::Range
+            }
+            Expr::ObjectValue { object, name } => match self.enum_name_of(object) {
+                Some(enum_name) => {
+                    self.variant_fields(&enum_name, name, span);
+
+                    Type::Class(enum_name)
+                }
+                None => Type::Any,
+            },
+        }
+    }
+
+    fn infer_binop(&mut self, op: &Token, left: Type, right: Type, span: Span) -> Type {
+        // Pookies can overload operators, so there is nothing to say about them here
+        if matches!(left, Type::Class(_)) || matches!(right, Type::Class(_)) {
+            return match op {
+                Token::GreaterThan | Token::LessThan | Token::Rizz | Token::And | Token::Or => Type::Boolean,
+                _ => Type::Any,
+            };
+        }
+
+        match op {
+            // Anything can be a condition, see Interpreter::is_truthy
+            Token::And | Token::Or => Type::Boolean,
+            Token::Plus => match (&left, &right) {
+                (Type::Number, Type::Number) => Type::Number,
+                (Type::String, Type::String) => Type::String,
+                (Type::Number, other) | (other, Type::Number) if *other != Type::Any => {
+                    self.expect_type(&Type::Number, other, "operand of +".to_string(), span);
+                    Type::Any
+                }
+                (Type::String, other) | (other, Type::String) if *other != Type::Any => {
+                    self.expect_type(&Type::String, other, "operand of +".to_string(), span);
+                    Type::Any
+                }
+                _ => Type::Any,
+            },
+            Token::Minus | Token::Star | Token::Slash => {
+                self.expect_type(&Type::Number, &left, format!("operand of {}", op), span);
+                self.expect_type(&Type::Number, &right, format!("operand of {}", op), span);
+
+                Type::Number
+            }
+            // Numbers, strings and lists can be ordered, as long as both sides are the same
+            Token::GreaterThan | Token::LessThan => {
+                let context = format!("operand of {}", op);
+
+                match (&left, &right) {
+                    (Type::Any, _) | (_, Type::Any) => {}
+                    (Type::String, _) | (Type::List, _) => self.expect_type(&left, &right, context, span),
+                    _ => {
+                        self.expect_type(&Type::Number, &left, context.clone(), span);
+                        self.expect_type(&Type::Number, &right, context, span);
+                    }
+                }
+
+                Type::Boolean
+            }
+            Token::Rizz => Type::Boolean,
+            _ => Type::Any,
+        }
+    }
+
+    fn infer_call(&mut self, name: &str, object: Option<&Expr>, args: &[Expr], span: Span) -> Type {
+        let object = match object {
+            Some(object) => object,
+            None => return self.infer_plain_call(name, args, span),
+        };
+
+        if let Some(enum_name) = self.enum_name_of(object) {
+            if let Some(fields) = self.variant_fields(&enum_name, name, span) {
+                let signature = Signature::new(vec![Type::Any; fields], Type::Class(enum_name.clone()));
+                return self.check_call(&format!("{}.{}", enum_name, name), &signature, args, span);
+            }
+
+            return Type::Class(enum_name);
+        }
+
+        let signature = match object {
+            Expr::Ident(object_name) if self.libs.contains_key(object_name) => self
+                .libs
+                .get(object_name)
+                .and_then(|lib| lib.get(name))
+                .cloned()
+                .map(|signature| (format!("{}.{}", object_name, name), signature)),
+            Expr::Ident(object_name) => {
+                let class_name = if object_name == "goat" {
+                    self.current_class.clone()
+                } else {
+                    match self.infer(object, span) {
+                        Type::Class(class_name) => Some(class_name),
+                        _ => None,
+                    }
+                };
+
+                class_name.and_then(|class_name| {
+                    self.classes
+                        .get(&class_name)
+                        .and_then(|methods| methods.get(name))
+                        .cloned()
+                        .map(|signature| (format!("{}.{}", class_name, name), signature))
+                })
+            }
+            _ => None,
+        };
+
+        match signature {
+            Some((full_name, signature)) => self.check_call(&full_name, &signature, args, span),
+            None => {
+                for arg in args {
+                    self.infer(arg, span);
+                }
+
+                Type::Any
+            }
+        }
+    }
+
+    fn infer_plain_call(&mut self, name: &str, args: &[Expr], span: Span) -> Type {
+        let signature = match name {
+            // yap takes anything, as many as you want, gimme has an optional fallback
+            "yap" | "gimme" => {
+                for arg in args {
+                    self.infer(arg, span);
+                }
+
+                return if name == "yap" { Type::Boolean } else { Type::Any };
+            }
+            "aura" => Signature::new(vec![Type::Any], Type::Number),
+            "attemptrizz" => Signature::new(vec![Type::String], Type::String),
+            "listmaxx" => Signature::new(vec![Type::Any], Type::List),
+            "thicc" => Signature::new(vec![Type::Any], Type::Number),
+            _ => match self.functions.get(name) {
+                Some(signature) => signature.clone(),
+                None => {
+                    for arg in args {
+                        self.infer(arg, span);
+                    }
+
+                    return Type::Any;
+                }
+            },
+        };
+
+        self.check_call(name, &signature, args, span)
+    }
+
+    fn check_call(&mut self, name: &str, signature: &Signature, args: &[Expr], span: Span) -> Type {
+        if args.len() != signature.params.len() {
+            self.errors.push(error::ParseError::ArgumentMismatch {
+                expected: signature.params.len(),
+                found: args.len(),
+                span,
+            });
+        }
+
+        for (index, arg) in args.iter().enumerate() {
+            let found = self.infer(arg, span);
+
+            if let Some(expected) = signature.params.get(index) {
+                self.expect_type(expected, &found, format!("argument {} of {}", index + 1, name), span);
+            }
+        }
+
+        signature.returns.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::Lexer, parser::Parser};
+
+    fn errors(source: &str) -> Vec<error::ParseError> {
+        let mut lexer = Lexer::new(source);
+        let (statements, parse_errors) = Parser::new(&mut lexer).unwrap().parse();
+        assert!(parse_errors.is_empty(), "{:?}", parse_errors);
+
+        check(&statements)
+    }
+
+    #[test]
+    fn annotations_that_agree_pass() {
+        let source = "\
+cookable double(x: number) -> number
+    blud x * 2
+slay
+
+based LIVES: number is cook double(3)
+";
+
+        assert!(errors(source).is_empty());
+    }
+
+    #[test]
+    fn mismatched_annotation() {
+        let errors = errors("score: number is \"ten\"\n");
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code(), "E0008");
+    }
+
+    #[test]
+    fn wrong_argument_type() {
+        let source = "\
+cookable double(x: number) -> number
+    blud x * 2
+slay
+
+y is cook double(\"two\")
+";
+
+        assert_eq!(errors(source)[0].code(), "E0008");
+    }
+
+    #[test]
+    fn unknown_type_suggests_the_close_one() {
+        let errors = errors("cookable f(a: nubmer)\n    blud a\nslay\n");
+
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            error::ParseError::UnknownType { name, suggestion: Some(suggestion), .. }
+                if name == "nubmer" && suggestion == "number"
+        ));
+    }
+
+    #[test]
+    fn pookies_and_vibes_are_types() {
+        let source = "\
+pookie Player()
+slay
+
+vibe State
+    Playing
+slay
+
+cookable f(p: Player, s: State) -> Player
+    blud p
+slay
+";
+
+        assert!(errors(source).is_empty());
+    }
+
+    #[test]
+    fn goon_and_vibecheck_names_shadow_globals() {
+        let source = "\
+vibe GameState
+    GameOver(score)
+slay
+
+i is \"x\"
+goon (i in 0..3) eat
+    cook yap(i + 1)
+slay
+
+score is \"none\"
+vibecheck (GameState.GameOver(3)) eat
+    GameState.GameOver(score) eat
+        cook yap(score * 2)
+    slay
+slay
+
+cookable f(n: number)
+    goon (i in 0..n) eat
+        cook yap(i + n)
+    slay
+slay
+";
+
+        assert!(errors(source).is_empty(), "{:?}", errors(source));
+
+        // Outside the loop it's the global again
+        let errors = errors(&format!("{}x is i + 1\n", source));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code(), "E0008");
+    }
+
+    #[test]
+    fn unknown_variant_in_a_pattern() {
+        let source = "\
+vibe GameState
+    Menu
+    GameOver(score)
+slay
+
+state is GameState.Menu
+vibecheck (state) eat
+    GameState.GameOvr(score) eat
+    slay
+    GameState.Mneu eat
+    slay
+    GameState.GameOver(a, b) eat
+    slay
+slay
+";
+        let errors = errors(source);
+        let codes: Vec<&str> = errors.iter().map(|e| e.code()).collect();
+
+        assert_eq!(codes, ["E0017", "E0017", "E0006"]);
+        assert!(matches!(
+            &errors[0],
+            error::ParseError::UnknownVariant { variant, suggestion: Some(suggestion), span, .. }
+                if variant == "GameOvr" && suggestion == "GameOver" && span.line == 8
+        ));
+    }
+
+    #[test]
+    fn unknown_type_has_no_mismatch_on_top() {
+        let errors = errors("x: lits is [1]\n");
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code(), "E0016");
+    }
+}